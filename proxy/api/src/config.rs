@@ -39,6 +39,22 @@ pub fn store_dir() -> path::PathBuf {
     store_root.join("store")
 }
 
+/// Returns the directory to look for additional `.sublime-syntax` and `.tmTheme` files used for
+/// syntax highlighting.
+pub fn highlighting_dir() -> path::PathBuf {
+    dirs().config_dir().join("highlighting")
+}
+
+/// Returns the path to the armored OpenPGP public keys commit signatures are verified against.
+pub fn keyring_path() -> path::PathBuf {
+    dirs().config_dir().join("keyring.asc")
+}
+
+/// Returns the path to the GnuPG home the OpenPGP keyring is imported into.
+pub fn gnupg_dir() -> path::PathBuf {
+    dirs().data_dir().join("gnupg")
+}
+
 /// Returns the path to a folder containing helper binaries.
 ///
 /// # Errors
@@ -0,0 +1,71 @@
+//! Contributor statistics of project revisions, kept in the store since walking the full history
+//! is expensive.
+
+use coco::source::contributors::Contributor;
+
+use crate::error;
+
+/// Name for the bucket used in [`kv::Store`].
+const BUCKET_NAME: &str = "contributors";
+
+/// Version of the statistics, part of every key. Bumped whenever the way they are computed
+/// changes, so entries computed the old way are no longer served.
+const VERSION: u32 = 3;
+
+/// Maximum number of revisions to keep statistics for. Once reached, the bucket is emptied.
+const CAPACITY: usize = 256;
+
+/// Returns the contributors to the history of the `revision` in the project `urn`, as seen by
+/// `peer_id`. The statistics are computed once per head commit and served from the `store`
+/// afterwards, as long as their [`VERSION`] matches.
+///
+/// # Errors
+///
+/// * If the revision can't be resolved or the history can't be walked.
+/// * If the store can't be accessed.
+pub async fn get(
+    state: &coco::State,
+    store: &kv::Store,
+    urn: coco::Urn,
+    peer_id: Option<coco::PeerId>,
+    maybe_revision: Option<coco::Revision<coco::PeerId>>,
+) -> Result<Vec<Contributor>, error::Error> {
+    let branch = state.get_branch(urn, peer_id, None).await?;
+    let head = state
+        .with_browser(branch.clone(), |browser| {
+            coco::source::head(browser, maybe_revision)
+        })
+        .await?;
+    let key = format!("{}/{}", VERSION, head);
+
+    if let Some(contributors) = store
+        .bucket::<&str, kv::Json<Vec<Contributor>>>(Some(BUCKET_NAME))?
+        .get(key.as_str())?
+    {
+        return Ok(contributors.to_inner());
+    }
+
+    let contributors = state
+        .with_browser_and_repo(branch, |browser, repo| {
+            coco::contributors(
+                browser,
+                repo,
+                Some(coco::Revision::<coco::PeerId>::Sha { sha: head }),
+            )
+        })
+        .await?;
+
+    let bucket = store.bucket::<&str, kv::Json<Vec<Contributor>>>(Some(BUCKET_NAME))?;
+    if bucket.iter().count() >= CAPACITY {
+        let mut keys = vec![];
+        for item in bucket.iter() {
+            keys.push(item?.key::<String>()?);
+        }
+        for key in keys {
+            bucket.remove(key.as_str())?;
+        }
+    }
+    bucket.set(key.as_str(), kv::Json(contributors.clone()))?;
+
+    Ok(contributors)
+}
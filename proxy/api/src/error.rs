@@ -36,4 +36,9 @@ pub enum Error {
     /// Errors stemming from [`coco::request::waiting_room::WaitingRoom`] interactions.
     #[error(transparent)]
     WaitingRoom(#[from] coco::request::waiting_room::Error),
+
+    /// A query string for a link to another endpoint could not be built.
+    // `serde_qs::Error` is not `Sync`, so only its message is kept.
+    #[error("failed to serialize query string: {0}")]
+    QueryString(String),
 }
@@ -12,6 +12,7 @@ mod identity;
 mod keystore;
 mod notification;
 mod project;
+mod search;
 mod session;
 mod source;
 
@@ -55,6 +56,7 @@ pub fn api(
     let notification_filter =
         path("notifications").and(notification::filters(ctx.clone(), subscriptions));
     let project_filter = path("projects").and(project::filters(ctx.clone()));
+    let search_filter = path("search").and(search::filters(ctx.clone()));
     let session_filter = path("session").and(session::filters(ctx.clone()));
     let keystore_filter = path("keystore").and(keystore::filters(ctx.clone()));
     let source_filter = path("source").and(source::filters(ctx));
@@ -65,6 +67,7 @@ pub fn api(
         identity_filter,
         notification_filter,
         project_filter,
+        search_filter,
         session_filter,
         keystore_filter,
         source_filter
@@ -228,16 +231,23 @@ pub fn guard_self_revision(
     state: &coco::State,
     revision: Option<coco::Revision<coco::PeerId>>,
 ) -> Option<coco::Revision<coco::PeerId>> {
-    revision.map(|r| {
-        if let coco::Revision::Branch { name, peer_id } = r {
-            coco::Revision::Branch {
-                name,
-                peer_id: guard_self_peer_id(state, peer_id),
-            }
-        } else {
-            r
+    revision.map(|r| guard_self_given_revision(state, r))
+}
+
+/// Like [`guard_self_revision`], for a `Revision` which is always present.
+#[must_use]
+pub fn guard_self_given_revision(
+    state: &coco::State,
+    revision: coco::Revision<coco::PeerId>,
+) -> coco::Revision<coco::PeerId> {
+    if let coco::Revision::Branch { name, peer_id } = revision {
+        coco::Revision::Branch {
+            name,
+            peer_id: guard_self_peer_id(state, peer_id),
         }
-    })
+    } else {
+        revision
+    }
 }
 
 #[cfg(test)]
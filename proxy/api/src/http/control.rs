@@ -84,7 +84,7 @@ mod handler {
             .map_err(error::Error::from)?;
         let stats = ctx
             .state
-            .with_browser(branch, |browser| Ok(browser.get_stats()?))
+            .with_browser_and_repo(branch, project::Stats::compute)
             .await
             .map_err(error::Error::from)?;
         let project: project::Full = (meta, stats).into();
@@ -5,7 +5,10 @@ use serde::Serialize;
 use std::convert::Infallible;
 use warp::{http::StatusCode, reject, reply, Rejection, Reply};
 
-use coco::{project::create, state};
+use coco::{
+    project::{create, update},
+    state,
+};
 
 use crate::error;
 
@@ -104,6 +107,9 @@ pub async fn recover(err: Rejection) -> Result<impl Reply, Infallible> {
                             include_error.to_string(),
                         ),
                     },
+                    coco::state::Error::Create(create::Error::Template(err)) => {
+                        (StatusCode::BAD_REQUEST, "INVALID_TEMPLATE", err.to_string())
+                    },
                     coco::state::Error::Create(create::Error::Validation(err)) => match err {
                         create::validation::Error::AlreadExists(_) => {
                             (StatusCode::CONFLICT, "PATH_EXISTS", err.to_string())
@@ -111,11 +117,17 @@ pub async fn recover(err: Rejection) -> Result<impl Reply, Infallible> {
                         create::validation::Error::EmptyExistingPath(_) => {
                             (StatusCode::BAD_REQUEST, "EMPTY_PATH", err.to_string())
                         },
+                        create::validation::Error::EmptyImportUrl(_) => {
+                            (StatusCode::BAD_REQUEST, "EMPTY_URL", err.to_string())
+                        },
                         create::validation::Error::Git(_) => (
                             StatusCode::INTERNAL_SERVER_ERROR,
                             "GIT_ERROR",
                             err.to_string(),
                         ),
+                        create::validation::Error::Import { .. } => {
+                            (StatusCode::BAD_REQUEST, "IMPORT_FAILED", err.to_string())
+                        },
                         create::validation::Error::MissingAuthorEmail => (
                             StatusCode::BAD_REQUEST,
                             "MISSING_AUTHOR_EMAIL",
@@ -147,9 +159,17 @@ pub async fn recover(err: Rejection) -> Result<impl Reply, Infallible> {
                         create::validation::Error::NotARepo(_) => {
                             (StatusCode::BAD_REQUEST, "NOT_A_REPO", err.to_string())
                         },
+                        create::validation::Error::RemoteImport(_) => {
+                            (StatusCode::BAD_REQUEST, "REMOTE_IMPORT", err.to_string())
+                        },
                         create::validation::Error::Io(err) => {
                             (StatusCode::BAD_REQUEST, "IO_ERROR", err.to_string())
                         },
+                        create::validation::Error::UnexpectedTemplate => (
+                            StatusCode::BAD_REQUEST,
+                            "UNEXPECTED_TEMPLATE",
+                            err.to_string(),
+                        ),
                         create::validation::Error::UrlMismatch { .. } => {
                             (StatusCode::BAD_REQUEST, "URL_MISMATCH", err.to_string())
                         },
@@ -168,6 +188,12 @@ pub async fn recover(err: Rejection) -> Result<impl Reply, Infallible> {
                         "NOT_FOUND",
                         "entity not found".to_string(),
                     ),
+                    coco::state::Error::Update(update::Error::NotMaintainer(_)) => {
+                        (StatusCode::FORBIDDEN, "NOT_MAINTAINER", err.to_string())
+                    },
+                    coco::state::Error::Update(update::Error::NoMaintainers) => {
+                        (StatusCode::BAD_REQUEST, "NO_MAINTAINERS", err.to_string())
+                    },
                     coco::state::Error::Git(git_error) => (
                         StatusCode::BAD_REQUEST,
                         "GIT_ERROR",
@@ -178,6 +204,17 @@ pub async fn recover(err: Rejection) -> Result<impl Reply, Infallible> {
                         "GIT_ERROR",
                         format!("Internal Git error: {}", git_error),
                     ),
+                    coco::state::Error::Source(coco::source::Error::CursorNotFound(_)) => {
+                        (StatusCode::BAD_REQUEST, "UNKNOWN_CURSOR", err.to_string())
+                    },
+                    coco::state::Error::Source(coco::source::Error::InvalidLimit) => {
+                        (StatusCode::BAD_REQUEST, "INVALID_LIMIT", err.to_string())
+                    },
+                    coco::state::Error::Source(coco::source::Error::InvalidSimilarity(_)) => (
+                        StatusCode::BAD_REQUEST,
+                        "INVALID_SIMILARITY",
+                        err.to_string(),
+                    ),
                     coco::state::Error::Source(coco::source::Error::NoBranches) => (
                         StatusCode::BAD_REQUEST,
                         "GIT_ERROR",
@@ -186,6 +223,14 @@ pub async fn recover(err: Rejection) -> Result<impl Reply, Infallible> {
                     coco::state::Error::Source(coco::source::Error::PathNotFound(path)) => {
                         (StatusCode::NOT_FOUND, "NOT_FOUND", path.to_string())
                     },
+                    coco::state::Error::Source(coco::source::Error::TagNotFound(name)) => {
+                        (StatusCode::NOT_FOUND, "NOT_FOUND", name.to_string())
+                    },
+                    coco::state::Error::Source(coco::source::Error::Regex(err)) => (
+                        StatusCode::BAD_REQUEST,
+                        "INVALID_SEARCH_QUERY",
+                        err.to_string(),
+                    ),
                     _ => {
                         // TODO(xla): Match all variants and properly transform similar to
                         // gaphql::error.
@@ -11,9 +11,13 @@ mod request;
 
 /// Combination of all routes.
 pub fn filters(ctx: context::Context) -> BoxedFilter<(impl Reply,)> {
-    checkout_filter(ctx.clone())
+    archive_filter(ctx.clone())
+        .or(archived_filter(ctx.clone()))
+        .or(checkout_filter(ctx.clone()))
         .or(create_filter(ctx.clone()))
+        .or(delete_filter(ctx.clone()))
         .or(failed_filter(ctx.clone()))
+        .or(fork_filter(ctx.clone()))
         .or(get_filter(ctx.clone()))
         .or(owner_contributed_filter(ctx.clone()))
         .or(owner_tracked_filter(ctx.clone()))
@@ -21,11 +25,36 @@ pub fn filters(ctx: context::Context) -> BoxedFilter<(impl Reply,)> {
         .or(path("requests").and(request::filters(ctx.clone())))
         .or(track_filter(ctx.clone()))
         .or(track_filter(ctx.clone()))
+        .or(unarchive_filter(ctx.clone()))
         .or(untrack_filter(ctx.clone()))
+        .or(update_filter(ctx.clone()))
         .or(user_filter(ctx))
         .boxed()
 }
 
+/// `PUT /<urn>/archive`
+fn archive_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path::param::<coco::Urn>()
+        .and(path("archive"))
+        .and(path::end())
+        .and(warp::put())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::archive)
+}
+
+/// `GET /archived`
+fn archived_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("archived")
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::list_archived)
+}
+
 /// `POST /<urn>/checkout`
 fn checkout_filter(
     ctx: context::Context,
@@ -51,6 +80,17 @@ fn create_filter(
         .and_then(handler::create)
 }
 
+/// `DELETE /<urn>`
+fn delete_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path::param::<coco::Urn>()
+        .and(path::end())
+        .and(warp::delete())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::delete)
+}
+
 /// `GET /failed`
 fn failed_filter(
     ctx: context::Context,
@@ -62,6 +102,20 @@ fn failed_filter(
         .and_then(handler::list_failed)
 }
 
+/// `POST /<urn>/fork`
+fn fork_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path::param::<coco::Urn>()
+        .and(path("fork"))
+        .and(path::end())
+        .and(warp::post())
+        .and(http::with_context_unsealed(ctx.clone()))
+        .and(http::with_owner_guard(ctx))
+        .and(warp::body::json())
+        .and_then(handler::fork)
+}
+
 /// `GET /<urn>`
 fn get_filter(
     ctx: context::Context,
@@ -107,6 +161,31 @@ fn peers_filter(
         .and_then(handler::peers)
 }
 
+/// `PUT /<urn>/unarchive`
+fn unarchive_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path::param::<coco::Urn>()
+        .and(path("unarchive"))
+        .and(path::end())
+        .and(warp::put())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::unarchive)
+}
+
+/// `PATCH /<urn>`
+fn update_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path::param::<coco::Urn>()
+        .and(path::end())
+        .and(warp::patch())
+        .and(http::with_context_unsealed(ctx.clone()))
+        .and(http::with_owner_guard(ctx))
+        .and(warp::body::json())
+        .and_then(handler::update)
+}
+
 /// `PUT /<urn>/track/<peer_id>`
 fn track_filter(
     ctx: context::Context,
@@ -150,7 +229,18 @@ fn user_filter(
 mod handler {
     use warp::{http::StatusCode, reply, Rejection, Reply};
 
-    use crate::{context, error::Error, http, project};
+    use crate::{context, error::Error, http, project, search};
+
+    /// Archive a [`project::Project`], which stops it from being announced and synced.
+    pub async fn archive(urn: coco::Urn, ctx: context::Unsealed) -> Result<impl Reply, Rejection> {
+        ctx.state
+            .get_project(urn.clone(), None)
+            .await
+            .map_err(Error::from)?;
+        coco::project::archive::archive(&ctx.store, urn).map_err(Error::from)?;
+
+        Ok(reply::json(&true))
+    }
 
     /// Checkout a [`project::Project`]'s source code.
     pub async fn checkout(
@@ -187,9 +277,7 @@ mod handler {
             .map_err(Error::from)?;
         let stats = ctx
             .state
-            .with_browser(branch, |browser| {
-                browser.get_stats().map_err(coco::source::Error::from)
-            })
+            .with_browser_and_repo(branch, project::Stats::compute)
             .await
             .map_err(Error::from)?;
         let project: project::Full = (meta, stats).into();
@@ -200,28 +288,74 @@ mod handler {
         ))
     }
 
+    /// Fork a [`project::Project`] into a new project owned by the current user.
+    pub async fn fork(
+        urn: coco::Urn,
+        ctx: context::Unsealed,
+        owner: coco::user::User,
+        super::ForkInput { name, peer_id }: super::ForkInput,
+    ) -> Result<impl Reply, Rejection> {
+        let peer_id = http::guard_self_peer_id(&ctx.state, peer_id);
+        let meta = ctx
+            .state
+            .fork_project(&owner, urn, peer_id, name)
+            .await
+            .map_err(Error::from)?;
+        coco::peer::announce_project(&ctx.state, &ctx.store, &meta.urn())
+            .await
+            .map_err(Error::from)?;
+        let project = project::get(&ctx.state, meta.urn()).await?;
+
+        Ok(reply::with_status(
+            reply::json(&project),
+            StatusCode::CREATED,
+        ))
+    }
+
     /// Get the [`project::Project`] for the given `id`.
     pub async fn get(urn: coco::Urn, ctx: context::Unsealed) -> Result<impl Reply, Rejection> {
         Ok(reply::json(&project::get(&ctx.state, urn).await?))
     }
 
+    /// Remove a [`project::Project`] from the device, together with everything known about it.
+    pub async fn delete(urn: coco::Urn, ctx: context::Unsealed) -> Result<impl Reply, Rejection> {
+        ctx.state
+            .delete_project(urn.clone())
+            .await
+            .map_err(Error::from)?;
+        coco::peer::forget_announcements(&ctx.store, &urn).map_err(Error::from)?;
+        search::remove_project(&ctx.store, &urn)?;
+        coco::project::archive::unarchive(&ctx.store, urn).map_err(Error::from)?;
+
+        Ok(reply::with_status(reply(), StatusCode::NO_CONTENT))
+    }
+
+    /// List all archived projects.
+    pub async fn list_archived(ctx: context::Unsealed) -> Result<impl Reply, Rejection> {
+        let projects = project::Projects::list(&ctx.state, &ctx.store).await?;
+
+        Ok(reply::json(&projects.archived))
+    }
+
     /// List all failed projects.
     pub async fn list_failed(ctx: context::Unsealed) -> Result<impl Reply, Rejection> {
-        let projects = project::Projects::list(&ctx.state).await?;
+        let projects = project::Projects::list(&ctx.state, &ctx.store).await?;
 
         Ok(reply::json(&projects.failures))
     }
 
     /// List all projects the current user has contributed to.
     pub async fn list_owner_contributed(ctx: context::Unsealed) -> Result<impl Reply, Rejection> {
-        let projects = project::Projects::list(&ctx.state).await?;
+        let projects = project::Projects::list(&ctx.state, &ctx.store).await?;
 
         Ok(reply::json(&projects.contributed))
     }
 
     /// List all projects tracked by the current user.
     pub async fn list_owner_tracked(ctx: context::Unsealed) -> Result<impl Reply, Rejection> {
-        let projects = project::Projects::list(&ctx.state).await?.tracked;
+        let projects = project::Projects::list(&ctx.state, &ctx.store)
+            .await?
+            .tracked;
 
         Ok(reply::json(&projects))
     }
@@ -253,6 +387,25 @@ mod handler {
         Ok(reply::json(&peers))
     }
 
+    /// Change the metadata of a [`project::Project`].
+    pub async fn update(
+        urn: coco::Urn,
+        ctx: context::Unsealed,
+        owner: coco::user::User,
+        input: coco::project::Update,
+    ) -> Result<impl Reply, Rejection> {
+        let meta = ctx
+            .state
+            .update_project(&owner, urn.clone(), input)
+            .await
+            .map_err(Error::from)?;
+        coco::peer::announce_project(&ctx.state, &ctx.store, &meta.urn())
+            .await
+            .map_err(Error::from)?;
+
+        Ok(reply::json(&project::get(&ctx.state, urn).await?))
+    }
+
     /// Track the peer for the provided project.
     pub async fn track(
         urn: coco::Urn,
@@ -263,6 +416,16 @@ mod handler {
         Ok(reply::json(&true))
     }
 
+    /// Take a [`project::Project`] out of the archive.
+    pub async fn unarchive(
+        urn: coco::Urn,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let archived = coco::project::archive::unarchive(&ctx.store, urn).map_err(Error::from)?;
+
+        Ok(reply::json(&archived))
+    }
+
     /// Untrack the peer for the provided project.
     pub async fn untrack(
         urn: coco::Urn,
@@ -295,6 +458,16 @@ pub struct CheckoutInput {
     peer_id: Option<coco::PeerId>,
 }
 
+/// Bundled input data for forking a project.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkInput {
+    /// Name of the fork.
+    name: String,
+    /// Which peer's view of the project to seed the fork with. If it's `None`, we fork our own.
+    peer_id: Option<coco::PeerId>,
+}
+
 /// User provided metadata for project manipulation.
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -310,13 +483,15 @@ pub struct MetadataInput {
 #[allow(clippy::panic, clippy::unwrap_used)]
 #[cfg(test)]
 mod test {
+    use std::collections::HashSet;
+
     use pretty_assertions::assert_eq;
     use serde_json::{json, Value};
     use warp::{http::StatusCode, test::request};
 
     use radicle_surf::vcs::git::git2;
 
-    use crate::{context, http, identity, project, session};
+    use crate::{context, http, identity, project, search, session};
 
     #[tokio::test]
     async fn checkout() -> Result<(), Box<dyn std::error::Error>> {
@@ -425,6 +600,7 @@ mod test {
             },
             description: "Desktop client for radicle.".into(),
             default_branch: coco::control::default_branch(),
+            template: None,
         };
 
         let res = request()
@@ -434,7 +610,7 @@ mod test {
             .reply(&api)
             .await;
 
-        let projects = project::Projects::list(&ctx.state).await?;
+        let projects = project::Projects::list(&ctx.state, &ctx.store).await?;
         let meta = projects.into_iter().next().unwrap();
         let maintainer = meta.metadata.maintainers.iter().next().unwrap();
 
@@ -454,6 +630,7 @@ mod test {
                 "branches": 1,
                 "commits": 1,
                 "contributors": 1,
+                "languages": [],
             },
         });
 
@@ -464,6 +641,156 @@ mod test {
     }
 
     #[tokio::test]
+    async fn create_from_template() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let repos_dir = tempfile::tempdir_in(tmp_dir.path())?;
+        let dir = tempfile::tempdir_in(repos_dir.path())?;
+        let template_dir = tempfile::tempdir_in(tmp_dir.path())?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        {
+            let handle = "cloudhead";
+            let id = identity::create(&ctx.state, handle).await?;
+
+            session::initialize(&ctx.store, id, &ctx.default_seeds)?;
+        };
+
+        std::fs::write(
+            template_dir.path().join("README.md"),
+            "# {{name}}\n\n{{description}}\n",
+        )?;
+        std::fs::write(template_dir.path().join("LICENSE"), "GPL-3.0-only\n")?;
+
+        let project = coco::project::Create {
+            repo: coco::project::Repo::New {
+                path: dir.path().to_path_buf(),
+                name: "Upstream".to_string(),
+            },
+            description: "Desktop client for radicle.".into(),
+            default_branch: coco::control::default_branch(),
+            template: Some(coco::project::Template::Directory {
+                path: template_dir.path().to_path_buf(),
+            }),
+        };
+
+        let res = request()
+            .method("POST")
+            .path("/")
+            .json(&project)
+            .reply(&api)
+            .await;
+
+        assert_eq!(res.status(), StatusCode::CREATED);
+
+        let repo = git2::Repository::open(dir.path().join("Upstream"))?;
+        let tree = repo.head()?.peel_to_tree()?;
+        let readme = repo.find_blob(tree.get_name("README.md").unwrap().id())?;
+        assert_eq!(
+            std::str::from_utf8(readme.content())?,
+            "# Upstream\n\nDesktop client for radicle.\n"
+        );
+        assert!(tree.get_name("LICENSE").is_some());
+        assert!(repo.statuses(None)?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_from_project_template() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let repos_dir = tempfile::tempdir_in(tmp_dir.path())?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let owner = ctx.state.init_owner("cloudhead").await?;
+        session::initialize(
+            &ctx.store,
+            (ctx.state.peer_id(), owner.clone()).into(),
+            &ctx.default_seeds,
+        )?;
+
+        let template = ctx
+            .state
+            .init_project(
+                &owner,
+                coco::project::Create {
+                    repo: coco::project::Repo::New {
+                        path: repos_dir.path().to_path_buf(),
+                        name: "Template".to_string(),
+                    },
+                    description: "Project template.".into(),
+                    default_branch: coco::control::default_branch(),
+                    template: None,
+                },
+            )
+            .await?;
+
+        // The README with placeholders is replaced again after the revision we ask for.
+        let revision = {
+            let repo = git2::Repository::open(repos_dir.path().join("Template"))?;
+            let commit = |readme: &str| -> Result<git2::Oid, git2::Error> {
+                let mut builder = repo.treebuilder(None)?;
+                builder.insert("README.md", repo.blob(readme.as_bytes())?, 0o100_644)?;
+                let tree = repo.find_tree(builder.write()?)?;
+                let parent = repo.head()?.peel_to_commit()?;
+                let signature = git2::Signature::now("cloudhead", "cloudhead@radicle.xyz")?;
+                repo.commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    "Update README",
+                    &tree,
+                    &[&parent],
+                )
+            };
+            let revision = commit("# {{name}}\n\n{{description}}\n\nBranch: {{defaultBranch}}\n")?;
+            commit("# Template\n")?;
+            repo.find_remote("rad")?
+                .push(&["refs/heads/master:refs/heads/master"], None)?;
+            revision
+        };
+
+        let project = coco::project::Create {
+            repo: coco::project::Repo::New {
+                path: repos_dir.path().to_path_buf(),
+                name: "Upstream".to_string(),
+            },
+            description: "Desktop client for radicle.".into(),
+            default_branch: coco::control::default_branch(),
+            template: Some(coco::project::Template::Project {
+                urn: template.urn(),
+                revision: Some(coco::Revision::Sha {
+                    sha: revision.into(),
+                }),
+            }),
+        };
+
+        let res = request()
+            .method("POST")
+            .path("/")
+            .json(&project)
+            .reply(&api)
+            .await;
+
+        assert_eq!(res.status(), StatusCode::CREATED);
+
+        let repo = git2::Repository::open(repos_dir.path().join("Upstream"))?;
+        let initial = repo.head()?.peel_to_commit()?;
+        assert_eq!(initial.parent_count(), 0);
+        let tree = initial.tree()?;
+        assert_eq!(tree.len(), 1);
+        let readme = repo.find_blob(tree.get_name("README.md").unwrap().id())?;
+        assert_eq!(
+            std::str::from_utf8(readme.content())?,
+            "# Upstream\n\nDesktop client for radicle.\n\nBranch: master\n"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
     async fn create_existing() -> Result<(), Box<dyn std::error::Error>> {
         let tmp_dir = tempfile::tempdir()?;
         let repos_dir = tempfile::tempdir_in(tmp_dir.path())?;
@@ -484,6 +811,7 @@ mod test {
             },
             description: "Desktop client for radicle.".into(),
             default_branch: coco::control::default_branch(),
+            template: None,
         };
 
         // Create the repository for which we'll create a project for
@@ -512,11 +840,16 @@ mod test {
             .reply(&api)
             .await;
 
-        let projects = project::Projects::list(&ctx.state).await?;
+        let projects = project::Projects::list(&ctx.state, &ctx.store).await?;
         let meta = projects.into_iter().next().unwrap();
         let maintainer = meta.metadata.maintainers.iter().next().unwrap();
 
-        let have: Value = serde_json::from_slice(res.body()).unwrap();
+        let mut have: Value = serde_json::from_slice(res.body()).unwrap();
+        let languages = have["stats"]
+            .as_object_mut()
+            .unwrap()
+            .remove("languages")
+            .unwrap();
         let want = json!({
             "urn": meta.urn,
             "metadata": {
@@ -538,6 +871,73 @@ mod test {
         assert_eq!(res.status(), StatusCode::CREATED);
         assert_eq!(have, want);
 
+        // Plain text and files without an extension, like `bin/ls`, are left out.
+        let languages = languages.as_array().unwrap();
+        let mut names = languages
+            .iter()
+            .map(|language| language["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        assert_eq!(names, vec!["Haskell", "Markdown", "Rust"]);
+        assert!(languages
+            .iter()
+            .all(|language| language["bytes"].as_u64().unwrap() > 0));
+        let total = languages
+            .iter()
+            .map(|language| language["percentage"].as_f64().unwrap())
+            .sum::<f64>();
+        assert!((total - 100.0).abs() < 0.001);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn fork() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let urn = {
+            let owner = ctx.state.init_owner("cloudhead").await?;
+            session::initialize(
+                &ctx.store,
+                (ctx.state.peer_id(), owner.clone()).into(),
+                &ctx.default_seeds,
+            )?;
+
+            let platinum_project = coco::control::replicate_platinum(
+                &ctx.state,
+                &owner,
+                "git-platinum",
+                "fixture data",
+                coco::control::default_branch(),
+            )
+            .await?;
+            platinum_project.urn()
+        };
+
+        let input = super::ForkInput {
+            name: "platinum-fork".to_string(),
+            peer_id: None,
+        };
+        let res = request()
+            .method("POST")
+            .path(&format!("/{}/fork", urn))
+            .json(&input)
+            .reply(&api)
+            .await;
+
+        let upstream = project::get(&ctx.state, urn.clone()).await?;
+        http::test::assert_response(&res, StatusCode::CREATED, |have| {
+            assert_ne!(have["urn"], json!(urn));
+            assert_eq!(have["metadata"]["name"], "platinum-fork");
+            assert_eq!(have["metadata"]["description"], "fixture data");
+            assert_eq!(have["metadata"]["defaultBranch"], "master");
+            assert_eq!(have["metadata"]["upstream"], json!(urn));
+            assert_eq!(have["stats"], json!(upstream.stats));
+        });
+
         Ok(())
     }
 
@@ -587,7 +987,7 @@ mod test {
         let owner = ctx.state.init_owner("cloudhead").await?;
         coco::control::setup_fixtures(&ctx.state, &owner).await?;
 
-        let projects = project::Projects::list(&ctx.state).await?;
+        let projects = project::Projects::list(&ctx.state, &ctx.store).await?;
         let project = projects.into_iter().next().unwrap();
         let coco_project = ctx.state.get_project(project.urn.clone(), None).await?;
 
@@ -624,7 +1024,7 @@ mod test {
             .reply(&api)
             .await;
 
-        let projects = project::Projects::list(&ctx.state).await?;
+        let projects = project::Projects::list(&ctx.state, &ctx.store).await?;
 
         http::test::assert_response(&res, StatusCode::OK, |have| {
             assert_eq!(have, json!(projects.contributed));
@@ -633,6 +1033,156 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn update() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let owner = ctx.state.init_owner("cloudhead").await?;
+        session::initialize(
+            &ctx.store,
+            (ctx.state.peer_id(), owner.clone()).into(),
+            &ctx.default_seeds,
+        )?;
+        let urn = coco::control::replicate_platinum(
+            &ctx.state,
+            &owner,
+            "git-platinum",
+            "fixture data",
+            coco::control::default_branch(),
+        )
+        .await?
+        .urn();
+        let rafalca = ctx.state.init_user("rafalca").await?.urn();
+
+        let update = coco::project::Update {
+            description: Some("Fixture data.".to_string()),
+            add_maintainers: vec![rafalca.clone()],
+            ..coco::project::Update::default()
+        };
+        let res = request()
+            .method("PATCH")
+            .path(&format!("/{}", urn))
+            .json(&update)
+            .reply(&api)
+            .await;
+
+        let project = project::get(&ctx.state, urn.clone()).await?;
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, json!(project));
+            assert_eq!(have["metadata"]["description"], "Fixture data.");
+            assert_eq!(have["metadata"]["defaultBranch"], "master");
+        });
+        assert_eq!(
+            project.metadata.maintainers,
+            vec![owner.urn(), rafalca.clone()]
+                .into_iter()
+                .collect::<HashSet<_>>()
+        );
+
+        let update = coco::project::Update {
+            remove_maintainers: vec![rafalca],
+            ..coco::project::Update::default()
+        };
+        let res = request()
+            .method("PATCH")
+            .path(&format!("/{}", urn))
+            .json(&update)
+            .reply(&api)
+            .await;
+
+        let project = project::get(&ctx.state, urn.clone()).await?;
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, json!(project));
+            assert_eq!(have["metadata"]["description"], "Fixture data.");
+            assert_eq!(have["metadata"]["maintainers"], json!([owner.urn()]));
+        });
+
+        // Every maintainer can't be removed.
+        let update = coco::project::Update {
+            remove_maintainers: project.metadata.maintainers.into_iter().collect(),
+            ..coco::project::Update::default()
+        };
+        let res = request()
+            .method("PATCH")
+            .path(&format!("/{}", urn))
+            .json(&update)
+            .reply(&api)
+            .await;
+        http::test::assert_response(&res, StatusCode::BAD_REQUEST, |have| {
+            assert_eq!(have["variant"], "NO_MAINTAINERS");
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn update_fork() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let owner = ctx.state.init_owner("cloudhead").await?;
+        session::initialize(
+            &ctx.store,
+            (ctx.state.peer_id(), owner.clone()).into(),
+            &ctx.default_seeds,
+        )?;
+        let urn = coco::control::replicate_platinum(
+            &ctx.state,
+            &owner,
+            "git-platinum",
+            "fixture data",
+            coco::control::default_branch(),
+        )
+        .await?
+        .urn();
+        let fork = ctx
+            .state
+            .fork_project(&owner, urn.clone(), None, "platinum-fork".to_string())
+            .await?
+            .urn();
+
+        // The fork keeps pointing at its upstream.
+        let update = coco::project::Update {
+            description: Some("Forked fixture data.".to_string()),
+            ..coco::project::Update::default()
+        };
+        let res = request()
+            .method("PATCH")
+            .path(&format!("/{}", fork))
+            .json(&update)
+            .reply(&api)
+            .await;
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have["metadata"]["name"], "platinum-fork");
+            assert_eq!(have["metadata"]["description"], "Forked fixture data.");
+            assert_eq!(have["metadata"]["upstream"], json!(urn));
+        });
+
+        // Only maintainers may update a project.
+        let rafalca = coco::user::verify(ctx.state.init_user("rafalca").await?)?;
+        let other_fork = ctx
+            .state
+            .fork_project(&rafalca, urn, None, "rafalca-fork".to_string())
+            .await?
+            .urn();
+        let res = request()
+            .method("PATCH")
+            .path(&format!("/{}", other_fork))
+            .json(&update)
+            .reply(&api)
+            .await;
+        http::test::assert_response(&res, StatusCode::FORBIDDEN, |have| {
+            assert_eq!(have["variant"], "NOT_MAINTAINER");
+        });
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn track() -> Result<(), Box<dyn std::error::Error>> {
         let tmp_dir = tempfile::tempdir()?;
@@ -641,7 +1191,7 @@ mod test {
 
         let owner = ctx.state.init_owner("cloudhead").await?;
         coco::control::setup_fixtures(&ctx.state, &owner).await?;
-        let projects = project::Projects::list(&ctx.state).await?;
+        let projects = project::Projects::list(&ctx.state, &ctx.store).await?;
         let project = projects.contributed.first().expect("no projects setup");
 
         let res = request()
@@ -669,7 +1219,7 @@ mod test {
 
         let owner = ctx.state.init_owner("cloudhead").await?;
         coco::control::setup_fixtures(&ctx.state, &owner).await?;
-        let projects = project::Projects::list(&ctx.state).await?;
+        let projects = project::Projects::list(&ctx.state, &ctx.store).await?;
         let project = projects.contributed.first().expect("no projects setup");
 
         let res = request()
@@ -697,7 +1247,7 @@ mod test {
 
         let owner = ctx.state.init_owner("cloudhead").await?;
         coco::control::setup_fixtures(&ctx.state, &owner).await?;
-        let projects = project::Projects::list(&ctx.state).await?;
+        let projects = project::Projects::list(&ctx.state, &ctx.store).await?;
         let project = projects.contributed.first().expect("no projects setup");
 
         let res = request()
@@ -730,4 +1280,114 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn archive() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let owner = ctx.state.init_owner("cloudhead").await?;
+        coco::control::setup_fixtures(&ctx.state, &owner).await?;
+        let projects = project::Projects::list(&ctx.state, &ctx.store).await?;
+        let urn = projects
+            .contributed
+            .first()
+            .expect("no projects setup")
+            .urn
+            .clone();
+
+        let res = request()
+            .method("PUT")
+            .path(&format!("/{}/archive", urn))
+            .reply(&api)
+            .await;
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, true);
+        });
+
+        let archived = project::Projects::list(&ctx.state, &ctx.store).await?;
+        assert!(archived
+            .contributed
+            .iter()
+            .all(|project| project.urn != urn));
+        let res = request().method("GET").path("/archived").reply(&api).await;
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, json!(archived.archived));
+        });
+
+        let res = request()
+            .method("PUT")
+            .path(&format!("/{}/unarchive", urn))
+            .reply(&api)
+            .await;
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, true);
+        });
+
+        let unarchived = project::Projects::list(&ctx.state, &ctx.store).await?;
+        assert!(unarchived.archived.is_empty());
+        assert_eq!(unarchived.contributed.len(), projects.contributed.len());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn delete() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let search = super::super::search::filters(ctx.clone().into());
+
+        let owner = ctx.state.init_owner("cloudhead").await?;
+        coco::control::setup_fixtures(&ctx.state, &owner).await?;
+        search::index_all(&ctx.state, &ctx.store).await?;
+        let projects = project::Projects::list(&ctx.state, &ctx.store).await?;
+        let (urn, name) = {
+            let project = projects.contributed.first().expect("no projects setup");
+            (project.urn.clone(), project.metadata.name.clone())
+        };
+        ctx.state
+            .track(urn.clone(), coco::control::generate_peer_id())
+            .await?;
+
+        let hits = |have: &Value| {
+            have["hits"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|hit| hit["urn"].clone())
+                .collect::<Vec<_>>()
+        };
+        let query = format!(
+            "/?query={}",
+            percent_encoding::utf8_percent_encode(&name, percent_encoding::NON_ALPHANUMERIC)
+        );
+        let res = request().method("GET").path(&query).reply(&search).await;
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert!(hits(&have).contains(&json!(urn)));
+        });
+
+        let res = request()
+            .method("DELETE")
+            .path(&format!("/{}", urn))
+            .reply(&api)
+            .await;
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+        let remaining = project::Projects::list(&ctx.state, &ctx.store).await?;
+        assert_eq!(remaining.contributed.len(), projects.contributed.len() - 1);
+        assert!(remaining.into_iter().all(|project| project.urn != urn));
+        assert!(ctx.state.get_project(urn.clone(), None).await.is_err());
+
+        // The project can't be found anymore.
+        let res = request().method("GET").path(&query).reply(&search).await;
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert!(!hits(&have).contains(&json!(urn)));
+        });
+
+        Ok(())
+    }
 }
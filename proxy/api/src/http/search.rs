@@ -0,0 +1,103 @@
+//! Endpoints to search across all projects on the device.
+
+use serde::{Deserialize, Serialize};
+use warp::{filters::BoxedFilter, path, Filter, Rejection, Reply};
+
+use crate::{context, http};
+
+/// Combination of all search routes.
+pub fn filters(ctx: context::Context) -> BoxedFilter<(impl Reply,)> {
+    search_filter(ctx).boxed()
+}
+
+/// `GET /?query=<query>&limit=<limit>`
+fn search_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path::end()
+        .and(warp::get())
+        .and(http::with_qs::<SearchQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::search)
+}
+
+/// Search handlers for conversion between core domain and http request fullfilment.
+mod handler {
+    use warp::{reply, Rejection, Reply};
+
+    use crate::{context, search};
+
+    /// Search the index of all projects for the query.
+    pub async fn search(
+        super::SearchQuery { query, limit }: super::SearchQuery,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let results = search::search(&ctx.store, &query, limit.unwrap_or(search::DEFAULT_LIMIT))?;
+
+        Ok(reply::json(&results))
+    }
+}
+
+/// Bundled query params to pass to the search handler.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    /// What to search for.
+    query: String,
+    /// Maximum number of matches.
+    limit: Option<usize>,
+}
+
+#[allow(clippy::indexing_slicing, clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use warp::{http::StatusCode, test::request};
+
+    use crate::{context, http, search, session};
+
+    #[tokio::test]
+    async fn search() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let owner = ctx.state.init_owner("cloudhead").await?;
+        session::initialize(
+            &ctx.store,
+            (ctx.state.peer_id(), owner.clone()).into(),
+            &ctx.default_seeds,
+        )?;
+        let platinum_project = coco::control::replicate_platinum(
+            &ctx.state,
+            &owner,
+            "git-platinum",
+            "fixture data",
+            coco::control::default_branch(),
+        )
+        .await?;
+        search::index_all(&ctx.state, &ctx.store).await?;
+
+        let query = super::SearchQuery {
+            query: "ARROWS".to_string(),
+            limit: None,
+        };
+        let path = format!("/?{}", serde_qs::to_string(&query).unwrap());
+        let res = request().method("GET").path(&path).reply(&api).await;
+
+        let want = search::search(&ctx.store, "arrows", search::DEFAULT_LIMIT)?;
+
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, json!(want));
+            assert_eq!(have["truncated"], json!(false));
+            assert_eq!(have["hits"][0]["urn"], json!(platinum_project.urn()));
+            assert!(have["hits"][0]["matches"]
+                .as_array()
+                .unwrap()
+                .contains(&json!({ "type": "path", "path": "text/arrows.txt" })));
+        });
+
+        Ok(())
+    }
+}
@@ -7,16 +7,54 @@ use crate::{context, http};
 
 /// Combination of all source filters.
 pub fn filters(ctx: context::Context) -> BoxedFilter<(impl Reply,)> {
-    blob_filter(ctx.clone())
+    archive_filter(ctx.clone())
+        .or(blame_filter(ctx.clone()))
+        .or(blob_filter(ctx.clone()))
         .or(branches_filter(ctx.clone()))
         .or(commit_filter(ctx.clone()))
+        .or(commits_stats_filter(ctx.clone()))
         .or(commits_filter(ctx.clone()))
+        .or(compare_filter(ctx.clone()))
+        .or(contributors_filter(ctx.clone()))
+        .or(graph_filter(ctx.clone()))
+        .or(languages_filter(ctx.clone()))
         .or(local_state_filter())
+        .or(raw_filter(ctx.clone()))
+        .or(readme_filter(ctx.clone()))
+        .or(search_filter(ctx.clone()))
+        .or(tag_filter(ctx.clone()))
         .or(tags_filter(ctx.clone()))
+        .or(themes_filter())
         .or(tree_filter(ctx))
         .boxed()
 }
 
+/// `GET /archive/<project_urn>?peerId=<peer_id>&revision=<revision>&format=<format>`
+fn archive_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("archive")
+        .and(path::param::<coco::Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs_opt::<ArchiveQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::archive)
+}
+
+/// `GET /blame/<project_urn>?revision=<revision>&path=<path>`
+fn blame_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("blame")
+        .and(path::param::<coco::Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<BlameQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::blame)
+}
+
 /// `GET /blob/<project_urn>?revision=<revision>&path=<path>`
 fn blob_filter(
     ctx: context::Context,
@@ -42,7 +80,7 @@ fn branches_filter(
         .and_then(handler::branches)
 }
 
-/// `GET /commit/<project_urn>/<sha1>`
+/// `GET /commit/<project_urn>/<sha1>?peerId=<peer_id>`
 fn commit_filter(
     ctx: context::Context,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
@@ -51,11 +89,14 @@ fn commit_filter(
         .and(path::param::<coco::oid::Oid>())
         .and(path::end())
         .and(warp::get())
+        .and(http::with_qs_opt::<CommitQuery>())
         .and(http::with_context_unsealed(ctx))
         .and_then(handler::commit)
 }
 
-/// `GET /commits/<project_urn>?revision=<revision>`
+/// `GET /commits/<project_urn>?revision=<revision>&path=<path>&after=<sha1>&limit=<limit>`
+///
+/// Additionally accepts `author`, `since` and `until` to narrow down the history.
 fn commits_filter(
     ctx: context::Context,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
@@ -67,6 +108,72 @@ fn commits_filter(
         .and_then(handler::commits)
 }
 
+/// `GET /commits/<project_urn>/stats?revision=<revision>`
+fn commits_stats_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("commits")
+        .and(warp::get())
+        .and(http::with_context_unsealed(ctx))
+        .and(path::param::<coco::Urn>())
+        .and(path("stats"))
+        .and(path::end())
+        .and(http::with_qs_opt::<CommitsStatsQuery>())
+        .and_then(handler::commits_stats)
+}
+
+/// `GET /compare/<project_urn>?base=<revision>&head=<revision>`
+fn compare_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("compare")
+        .and(path::param::<coco::Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<CompareQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::compare)
+}
+
+/// `GET /contributors/<project_urn>?peerId=<peer_id>&revision=<revision>`
+fn contributors_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("contributors")
+        .and(path::param::<coco::Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs_opt::<ContributorsQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::contributors)
+}
+
+/// `GET /graph/<project_urn>?revisions[]=<revision>&limit=<limit>`
+fn graph_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("graph")
+        .and(path::param::<coco::Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs_opt::<GraphQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::graph)
+}
+
+/// `GET /languages/<project_urn>?peerId=<peer_id>&revision=<revision>`
+fn languages_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("languages")
+        .and(path::param::<coco::Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs_opt::<LanguagesQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::languages)
+}
+
 /// `GET /local-state/<path>`
 fn local_state_filter() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
     path("local-state")
@@ -75,7 +182,61 @@ fn local_state_filter() -> impl Filter<Extract = impl Reply, Error = Rejection>
         .and_then(handler::local_state)
 }
 
-/// `GET /tags/<project_urn>?peer_id=<peer_id>`
+/// `GET /raw/<project_urn>/<path>?peerId=<peer_id>&revision=<revision>`
+fn raw_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("raw")
+        .and(path::param::<coco::Urn>())
+        .and(path::tail())
+        .and(warp::get())
+        .and(http::with_qs_opt::<RawQuery>())
+        .and(warp::header::optional::<String>("range"))
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::raw)
+}
+
+/// `GET /readme/<project_urn>?peerId=<peer_id>&revision=<revision>&prefix=<prefix>`
+fn readme_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("readme")
+        .and(path::param::<coco::Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs_opt::<ReadmeQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::readme)
+}
+
+/// `GET /search/<project_urn>?peerId=<peer_id>&revision=<revision>&query=<query>`
+///
+/// Additionally accepts `limit` and `context` to tune the size of the results.
+fn search_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("search")
+        .and(path::param::<coco::Urn>())
+        .and(path::end())
+        .and(warp::get())
+        .and(http::with_qs::<SearchQuery>())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::search)
+}
+
+/// `GET /tag/<project_urn>/<name>`
+fn tag_filter(
+    ctx: context::Context,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("tag")
+        .and(path::param::<coco::Urn>())
+        .and(path::tail())
+        .and(warp::get())
+        .and(http::with_context_unsealed(ctx))
+        .and_then(handler::tag)
+}
+
+/// `GET /tags/<project_urn>?peer_id=<peer_id>&details=<details>`
 fn tags_filter(
     ctx: context::Context,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
@@ -88,6 +249,14 @@ fn tags_filter(
         .and_then(handler::tags)
 }
 
+/// `GET /themes`
+fn themes_filter() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    path("themes")
+        .and(path::end())
+        .and(warp::get())
+        .and_then(handler::themes)
+}
+
 /// `GET /tree/<project_urn>?peerId=<peer_id>&prefix=<prefix>*revision=<revision>`
 fn tree_filter(
     ctx: context::Context,
@@ -103,11 +272,98 @@ fn tree_filter(
 
 /// Source handlers for conversion between core domain and http request fullfilment.
 mod handler {
-    use warp::{path::Tail, reply, Rejection, Reply};
+    use warp::{
+        http::{
+            header::{
+                ACCEPT_RANGES, CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_RANGE,
+                CONTENT_SECURITY_POLICY, CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS,
+            },
+            StatusCode,
+        },
+        path::Tail,
+        reply, Rejection, Reply,
+    };
 
     use coco::oid;
 
-    use crate::{context, error, session, session::settings};
+    use crate::{context, contributors, error, session, session::settings};
+
+    /// Download a [`coco::source::archive::Archive`] of a revision.
+    pub async fn archive(
+        project_urn: coco::Urn,
+        query: Option<super::ArchiveQuery>,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let super::ArchiveQuery {
+            peer_id,
+            revision,
+            format,
+        } = query.unwrap_or_default();
+        let peer_id = super::http::guard_self_peer_id(&ctx.state, peer_id);
+        let revision = super::http::guard_self_revision(&ctx.state, revision);
+
+        let project = ctx
+            .state
+            .get_project(project_urn.clone(), None)
+            .await
+            .map_err(error::Error::from)?;
+        let branch = ctx
+            .state
+            .get_branch(project_urn, peer_id, None)
+            .await
+            .map_err(error::Error::from)?;
+        let archive = ctx
+            .state
+            .with_browser_and_repo(branch, |browser, repo| {
+                coco::archive(
+                    browser,
+                    repo,
+                    revision,
+                    project.name(),
+                    format.unwrap_or_default(),
+                )
+            })
+            .await
+            .map_err(error::Error::from)?;
+
+        Ok(warp::http::Response::builder()
+            .header(CONTENT_TYPE, archive.format.content_type())
+            .header(
+                CONTENT_DISPOSITION,
+                super::content_disposition(&archive.file_name),
+            )
+            .body(warp::hyper::Body::wrap_stream(
+                archive.stream(ctx.state.monorepo()),
+            )))
+    }
+
+    /// Fetch the [`coco::Blame`] of a file.
+    pub async fn blame(
+        project_urn: coco::Urn,
+        super::BlameQuery {
+            path,
+            peer_id,
+            revision,
+        }: super::BlameQuery,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let peer_id = super::http::guard_self_peer_id(&ctx.state, peer_id);
+        let revision = super::http::guard_self_revision(&ctx.state, revision);
+        let branch = ctx
+            .state
+            .get_branch(project_urn, peer_id, None)
+            .await
+            .map_err(error::Error::from)?;
+        let blame = ctx
+            .state
+            .with_browser_and_repo(branch, |browser, repo| {
+                coco::blame(browser, repo, revision, &path)
+            })
+            .await
+            .map_err(error::Error::from)?;
+
+        Ok(reply::json(&blame))
+    }
 
     /// Fetch a [`coco::Blob`].
     pub async fn blob(
@@ -117,6 +373,9 @@ mod handler {
             peer_id,
             revision,
             highlight,
+            theme,
+            tokens,
+            lines,
         }: super::BlobQuery,
         ctx: context::Unsealed,
     ) -> Result<impl Reply, Rejection> {
@@ -126,13 +385,17 @@ mod handler {
         let peer_id = super::http::guard_self_peer_id(&ctx.state, peer_id);
         let revision = super::http::guard_self_revision(&ctx.state, revision);
 
-        let theme = if let Some(true) = highlight {
-            Some(match settings.appearance.theme {
+        let theme = theme.unwrap_or_else(|| {
+            match settings.appearance.theme {
                 settings::Theme::Dark => "base16-ocean.dark",
                 settings::Theme::Light => "base16-ocean.light",
-            })
-        } else {
-            None
+            }
+            .to_string()
+        });
+        let highlight = match (tokens, highlight) {
+            (Some(true), _) => coco::Highlight::Tokens(lines.unwrap_or_default()),
+            (_, Some(true)) => coco::Highlight::Html(&theme),
+            _ => coco::Highlight::None,
         };
 
         let branch = ctx
@@ -142,8 +405,8 @@ mod handler {
             .map_err(error::Error::from)?;
         let blob = ctx
             .state
-            .with_browser(branch, |mut browser| {
-                coco::blob(&mut browser, revision, &path, theme)
+            .with_browser_and_repo(branch, |browser, repo| {
+                coco::blob(browser, repo, revision, &path, highlight)
             })
             .await
             .map_err(error::Error::from)?;
@@ -178,8 +441,15 @@ mod handler {
     pub async fn commit(
         project_urn: coco::Urn,
         sha1: oid::Oid,
+        query: Option<super::CommitQuery>,
         ctx: context::Unsealed,
     ) -> Result<impl Reply, Rejection> {
+        let super::CommitQuery {
+            peer_id,
+            similarity,
+        } = query.unwrap_or_default();
+        let peer_id = peer_id.unwrap_or_else(|| ctx.state.peer_id());
+
         let default_branch = ctx
             .state
             .find_default_branch(project_urn)
@@ -187,8 +457,14 @@ mod handler {
             .map_err(error::Error::from)?;
         let commit = ctx
             .state
-            .with_browser(default_branch, |mut browser| {
-                coco::commit(&mut browser, sha1)
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::commit(
+                    browser,
+                    repo,
+                    sha1,
+                    &peer_id,
+                    similarity.unwrap_or(coco::source::changeset::DEFAULT_SIMILARITY),
+                )
             })
             .await
             .map_err(error::Error::from)?;
@@ -200,9 +476,25 @@ mod handler {
     pub async fn commits(
         ctx: context::Unsealed,
         project_urn: coco::Urn,
-        super::CommitsQuery { revision }: super::CommitsQuery,
+        super::CommitsQuery {
+            revision,
+            path,
+            after,
+            limit,
+            author,
+            since,
+            until,
+        }: super::CommitsQuery,
     ) -> Result<impl Reply, Rejection> {
         let revision = super::http::guard_self_revision(&ctx.state, revision);
+        let filter = coco::CommitsFilter {
+            path,
+            after,
+            limit,
+            author,
+            since,
+            until,
+        };
 
         let default_branch = ctx
             .state
@@ -211,8 +503,8 @@ mod handler {
             .map_err(error::Error::from)?;
         let commits = ctx
             .state
-            .with_browser(default_branch, |mut browser| {
-                coco::commits(&mut browser, revision)
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::commits(browser, repo, revision, &filter)
             })
             .await
             .map_err(error::Error::from)?;
@@ -220,6 +512,137 @@ mod handler {
         Ok(reply::json(&commits))
     }
 
+    /// Fetch the [`coco::Stats`] of a branch.
+    pub async fn commits_stats(
+        ctx: context::Unsealed,
+        project_urn: coco::Urn,
+        query: Option<super::CommitsStatsQuery>,
+    ) -> Result<impl Reply, Rejection> {
+        let revision =
+            super::http::guard_self_revision(&ctx.state, query.and_then(|query| query.revision));
+
+        let default_branch = ctx
+            .state
+            .find_default_branch(project_urn)
+            .await
+            .map_err(error::Error::from)?;
+        let stats = ctx
+            .state
+            .with_browser(default_branch, |browser| {
+                coco::commits_stats(browser, revision)
+            })
+            .await
+            .map_err(error::Error::from)?;
+
+        Ok(reply::json(&stats))
+    }
+
+    /// Fetch the [`coco::Comparison`] between two revisions.
+    pub async fn compare(
+        project_urn: coco::Urn,
+        super::CompareQuery {
+            base,
+            head,
+            similarity,
+        }: super::CompareQuery,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let base = super::http::guard_self_given_revision(&ctx.state, base);
+        let head = super::http::guard_self_given_revision(&ctx.state, head);
+
+        let default_branch = ctx
+            .state
+            .find_default_branch(project_urn)
+            .await
+            .map_err(error::Error::from)?;
+        let comparison = ctx
+            .state
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::compare(
+                    browser,
+                    repo,
+                    base,
+                    head,
+                    similarity.unwrap_or(coco::source::changeset::DEFAULT_SIMILARITY),
+                )
+            })
+            .await
+            .map_err(error::Error::from)?;
+
+        Ok(reply::json(&comparison))
+    }
+
+    /// Fetch the weekly activity of the authors in the history of a revision.
+    pub async fn contributors(
+        project_urn: coco::Urn,
+        query: Option<super::ContributorsQuery>,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let super::ContributorsQuery { peer_id, revision } = query.unwrap_or_default();
+        let peer_id = super::http::guard_self_peer_id(&ctx.state, peer_id);
+        let revision = super::http::guard_self_revision(&ctx.state, revision);
+
+        let contributors =
+            contributors::get(&ctx.state, &ctx.store, project_urn, peer_id, revision).await?;
+
+        Ok(reply::json(&contributors))
+    }
+
+    /// Fetch the commit graph of several revisions.
+    pub async fn graph(
+        project_urn: coco::Urn,
+        query: Option<super::GraphQuery>,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let super::GraphQuery { revisions, limit } = query.unwrap_or_default();
+        let revisions = revisions
+            .unwrap_or_default()
+            .into_iter()
+            .map(|revision| super::http::guard_self_given_revision(&ctx.state, revision))
+            .collect::<Vec<_>>();
+
+        let default_branch = ctx
+            .state
+            .find_default_branch(project_urn)
+            .await
+            .map_err(error::Error::from)?;
+        let graph = ctx
+            .state
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::graph(browser, repo, revisions, limit)
+            })
+            .await
+            .map_err(error::Error::from)?;
+
+        Ok(reply::json(&graph))
+    }
+
+    /// Fetch the breakdown of the languages of a revision.
+    pub async fn languages(
+        project_urn: coco::Urn,
+        query: Option<super::LanguagesQuery>,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let super::LanguagesQuery { peer_id, revision } = query.unwrap_or_default();
+        let peer_id = super::http::guard_self_peer_id(&ctx.state, peer_id);
+        let revision = super::http::guard_self_revision(&ctx.state, revision);
+
+        let branch = ctx
+            .state
+            .get_branch(project_urn, peer_id, None)
+            .await
+            .map_err(error::Error::from)?;
+        let languages = ctx
+            .state
+            .with_browser_and_repo(branch, |browser, repo| {
+                coco::languages(browser, repo, revision)
+            })
+            .await
+            .map_err(error::Error::from)?;
+
+        Ok(reply::json(&languages))
+    }
+
     /// Fetch the list [`coco::Branch`] for a local repository.
     pub async fn local_state(path: Tail) -> Result<impl Reply, Rejection> {
         let state = coco::local_state(path.as_str())
@@ -229,60 +652,347 @@ mod handler {
         Ok(reply::json(&state))
     }
 
-    /// Fetch the list [`coco::Tag`].
-    pub async fn tags(
+    /// Fetch the raw bytes of a file, or the part of them requested with a `Range` header.
+    pub async fn raw(
         project_urn: coco::Urn,
-        _query: super::TagQuery,
+        path: Tail,
+        query: Option<super::RawQuery>,
+        range: Option<String>,
         ctx: context::Unsealed,
     ) -> Result<impl Reply, Rejection> {
+        let super::RawQuery { peer_id, revision } = query.unwrap_or_default();
+        let peer_id = super::http::guard_self_peer_id(&ctx.state, peer_id);
+        let revision = super::http::guard_self_revision(&ctx.state, revision);
+        let path = percent_encoding::percent_decode_str(path.as_str())
+            .decode_utf8_lossy()
+            .into_owned();
+
         let branch = ctx
             .state
-            .find_default_branch(project_urn)
+            .get_branch(project_urn, peer_id, None)
             .await
             .map_err(error::Error::from)?;
-        let tags = ctx
+        // Only the requested part of the blob is copied out of the object database.
+        let (content_type, len, byte_range, content) = ctx
             .state
-            .with_browser(branch, |browser| coco::tags(browser))
+            .with_browser_and_repo(branch, |browser, repo| {
+                coco::raw(browser, repo, revision, &path, |blob| {
+                    let byte_range = super::byte_range(range.as_deref(), blob.len());
+                    let content = match byte_range {
+                        super::ByteRange::Full => blob.to_vec(),
+                        super::ByteRange::Partial { start, end } => {
+                            blob.get(start..=end).unwrap_or_default().to_vec()
+                        },
+                        super::ByteRange::Unsatisfiable => vec![],
+                    };
+                    (
+                        super::content_type(&path, blob),
+                        blob.len(),
+                        byte_range,
+                        content,
+                    )
+                })
+            })
             .await
             .map_err(error::Error::from)?;
 
-        Ok(reply::json(&tags))
+        // Files are served from the origin of the API, make sure they can't run scripts there.
+        let response = warp::http::Response::builder()
+            .header(CONTENT_TYPE, content_type)
+            .header(ACCEPT_RANGES, "bytes")
+            .header(X_CONTENT_TYPE_OPTIONS, "nosniff")
+            .header(CONTENT_SECURITY_POLICY, "sandbox");
+
+        Ok(match byte_range {
+            super::ByteRange::Full => response.header(CONTENT_LENGTH, len).body(content),
+            super::ByteRange::Partial { start, end } => response
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len))
+                .header(CONTENT_LENGTH, end - start + 1)
+                .body(content),
+            super::ByteRange::Unsatisfiable => response
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(CONTENT_RANGE, format!("bytes */{}", len))
+                .header(CONTENT_LENGTH, 0)
+                .body(vec![]),
+        })
     }
 
-    /// Fetch a [`coco::Tree`].
-    pub async fn tree(
+    /// Fetch the rendered [`coco::source::readme::Readme`] of a directory.
+    pub async fn readme(
         project_urn: coco::Urn,
-        super::TreeQuery {
-            prefix,
-            peer_id,
-            revision,
-        }: super::TreeQuery,
+        query: Option<super::ReadmeQuery>,
         ctx: context::Unsealed,
     ) -> Result<impl Reply, Rejection> {
+        let super::ReadmeQuery {
+            peer_id,
+            revision,
+            prefix,
+        } = query.unwrap_or_default();
         let peer_id = super::http::guard_self_peer_id(&ctx.state, peer_id);
         let revision = super::http::guard_self_revision(&ctx.state, revision);
+
+        let raw_base = format!("/v1/source/raw/{}", project_urn);
+        let raw_query = serde_qs::to_string(&super::RawQuery {
+            peer_id,
+            revision: revision.clone(),
+        })
+        .map_err(|err| error::Error::QueryString(err.to_string()))?;
+        let tree_base = format!("/v1/source/tree/{}", project_urn);
+        let tree_revision = revision.clone();
+        let url = move |link: coco::source::readme::Link<'_>| match link {
+            coco::source::readme::Link::Directory(path) => format!(
+                "{}?{}",
+                tree_base,
+                serde_qs::to_string(&super::TreeQuery {
+                    prefix: Some(path.to_string()),
+                    peer_id,
+                    revision: tree_revision.clone(),
+                })
+                .unwrap_or_default()
+            ),
+            coco::source::readme::Link::File(path) => format!(
+                "{}/{}?{}",
+                raw_base,
+                percent_encoding::utf8_percent_encode(path, super::PATH),
+                raw_query
+            ),
+        };
+
         let branch = ctx
             .state
             .get_branch(project_urn, peer_id, None)
             .await
             .map_err(error::Error::from)?;
-        let tree = ctx
+        let readme = ctx
             .state
-            .with_browser(branch, |mut browser| {
-                coco::tree(&mut browser, revision, prefix)
+            .with_browser(branch, |browser| {
+                coco::readme(browser, revision, prefix, url)
             })
             .await
             .map_err(error::Error::from)?;
 
-        Ok(reply::json(&tree))
+        Ok(reply::json(&readme))
     }
-}
 
-/// Bundled query params to pass to the commits handler.
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct CommitsQuery {
-    /// Revision to query at.
+    /// Search the blobs of a revision.
+    pub async fn search(
+        project_urn: coco::Urn,
+        super::SearchQuery {
+            peer_id,
+            revision,
+            query,
+            limit,
+            context,
+        }: super::SearchQuery,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let peer_id = super::http::guard_self_peer_id(&ctx.state, peer_id);
+        let revision = super::http::guard_self_revision(&ctx.state, revision);
+        let options = {
+            let default = coco::source::search::Options::default();
+            coco::source::search::Options {
+                limit: limit.unwrap_or(default.limit),
+                context: context.unwrap_or(default.context),
+            }
+        };
+
+        let branch = ctx
+            .state
+            .get_branch(project_urn, peer_id, None)
+            .await
+            .map_err(error::Error::from)?;
+        let results = ctx
+            .state
+            .with_browser_and_repo(branch, |browser, repo| {
+                coco::search(browser, repo, revision, &query, options)
+            })
+            .await
+            .map_err(error::Error::from)?;
+
+        Ok(reply::json(&results))
+    }
+
+    /// Fetch the list [`coco::Tag`].
+    pub async fn tags(
+        project_urn: coco::Urn,
+        query: super::TagQuery,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let branch = ctx
+            .state
+            .find_default_branch(project_urn)
+            .await
+            .map_err(error::Error::from)?;
+
+        if query.details.unwrap_or(false) {
+            let tags = ctx
+                .state
+                .with_browser_and_repo(branch, |browser, repo| coco::tags_details(browser, repo))
+                .await
+                .map_err(error::Error::from)?;
+
+            return Ok(reply::json(&tags));
+        }
+
+        let tags = ctx
+            .state
+            .with_browser(branch, |browser| coco::tags(browser))
+            .await
+            .map_err(error::Error::from)?;
+
+        Ok(reply::json(&tags))
+    }
+
+    /// Fetch the [`coco::TagDetails`] of a single tag.
+    pub async fn tag(
+        project_urn: coco::Urn,
+        name: Tail,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let name = percent_encoding::percent_decode_str(name.as_str())
+            .decode_utf8_lossy()
+            .into_owned();
+
+        let branch = ctx
+            .state
+            .find_default_branch(project_urn)
+            .await
+            .map_err(error::Error::from)?;
+        let tag = ctx
+            .state
+            .with_browser_and_repo(branch, |_browser, repo| coco::tag(repo, &name))
+            .await
+            .map_err(error::Error::from)?;
+
+        Ok(reply::json(&tag))
+    }
+
+    /// Fetch the names of the themes available for syntax highlighting.
+    pub async fn themes() -> Result<impl Reply, Rejection> {
+        Ok(reply::json(&coco::source::highlight::themes()))
+    }
+
+    /// Fetch a [`coco::Tree`].
+    pub async fn tree(
+        project_urn: coco::Urn,
+        super::TreeQuery {
+            prefix,
+            peer_id,
+            revision,
+        }: super::TreeQuery,
+        ctx: context::Unsealed,
+    ) -> Result<impl Reply, Rejection> {
+        let peer_id = super::http::guard_self_peer_id(&ctx.state, peer_id);
+        let revision = super::http::guard_self_revision(&ctx.state, revision);
+        let branch = ctx
+            .state
+            .get_branch(project_urn, peer_id, None)
+            .await
+            .map_err(error::Error::from)?;
+        let tree = ctx
+            .state
+            .with_browser_and_repo(branch, |browser, repo| {
+                coco::tree(browser, repo, revision, prefix)
+            })
+            .await
+            .map_err(error::Error::from)?;
+
+        Ok(reply::json(&tree))
+    }
+}
+
+/// Bundled query params to pass to the commits handler.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitsQuery {
+    /// Revision to query at.
+    revision: Option<coco::Revision<coco::PeerId>>,
+    /// Only list commits touching the file or directory under this path.
+    path: Option<String>,
+    /// Sha1 of the last commit of the previous page.
+    after: Option<coco::oid::Oid>,
+    /// Maximum number of commits to return, at least 1.
+    limit: Option<usize>,
+    /// Only list commits authored by this email address.
+    author: Option<String>,
+    /// Only list commits committed at or after this time, in seconds since the epoch.
+    since: Option<i64>,
+    /// Only list commits committed at or before this time, in seconds since the epoch.
+    until: Option<i64>,
+}
+
+/// Bundled query params to pass to the commits stats handler.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitsStatsQuery {
+    /// Revision to query at.
+    revision: Option<coco::Revision<coco::PeerId>>,
+}
+
+/// Bundled query params to pass to the commit handler.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitQuery {
+    /// PeerId of the owner of the browsed branch, whose key signatures are verified against.
+    peer_id: Option<coco::PeerId>,
+    /// Percentage of content files need to share to be reported as moved or copied.
+    similarity: Option<u16>,
+}
+
+/// Bundled query params to pass to the compare handler.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareQuery {
+    /// Revision to compare against.
+    base: coco::Revision<coco::PeerId>,
+    /// Revision to compare.
+    head: coco::Revision<coco::PeerId>,
+    /// Percentage of content files need to share to be reported as moved or copied.
+    similarity: Option<u16>,
+}
+
+/// Bundled query params to pass to the contributors handler.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributorsQuery {
+    /// PeerId to scope the query by.
+    peer_id: Option<coco::PeerId>,
+    /// Revision to query at.
+    revision: Option<coco::Revision<coco::PeerId>>,
+}
+
+/// Bundled query params to pass to the graph handler.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQuery {
+    /// Revisions whose history makes up the graph, the default branch if none are given.
+    revisions: Option<Vec<coco::Revision<coco::PeerId>>>,
+    /// Maximum number of commits to return, [`coco::source::graph::DEFAULT_LIMIT`] if not given.
+    limit: Option<usize>,
+}
+
+/// Bundled query params to pass to the archive handler.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveQuery {
+    /// PeerId to scope the query by.
+    peer_id: Option<coco::PeerId>,
+    /// Revision to query at.
+    revision: Option<coco::Revision<coco::PeerId>>,
+    /// Kind of archive, defaults to a gzip compressed tarball.
+    format: Option<coco::source::archive::Format>,
+}
+
+/// Bundled query params to pass to the blame handler.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlameQuery {
+    /// Location of the blob in tree.
+    path: String,
+    /// PeerId to scope the query by.
+    peer_id: Option<coco::PeerId>,
+    /// Revision to query at.
     revision: Option<coco::Revision<coco::PeerId>>,
 }
 
@@ -298,6 +1008,170 @@ pub struct BlobQuery {
     revision: Option<coco::Revision<coco::PeerId>>,
     /// Whether or not to syntax highlight the blob.
     highlight: Option<bool>,
+    /// Theme to highlight with, overriding the one derived from the session settings.
+    theme: Option<String>,
+    /// Whether or not to return lines of highlighting tokens instead of the content.
+    tokens: Option<bool>,
+    /// Lines to return tokens for, defaults to all of them.
+    lines: Option<coco::source::highlight::LineRange>,
+}
+
+/// Bundled query params to pass to the languages handler.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguagesQuery {
+    /// PeerId to scope the query by.
+    peer_id: Option<coco::PeerId>,
+    /// Revision to query at.
+    revision: Option<coco::Revision<coco::PeerId>>,
+}
+
+/// Bundled query params to pass to the raw handler.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawQuery {
+    /// PeerId to scope the query by.
+    peer_id: Option<coco::PeerId>,
+    /// Revision to query at.
+    revision: Option<coco::Revision<coco::PeerId>>,
+}
+
+/// Bundled query params to pass to the readme handler.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadmeQuery {
+    /// PeerId to scope the query by.
+    peer_id: Option<coco::PeerId>,
+    /// Revision to query at.
+    revision: Option<coco::Revision<coco::PeerId>>,
+    /// Directory to look for the README in, defaults to the root of the repo.
+    prefix: Option<String>,
+}
+
+/// Characters to escape in the `filename*` parameter of a `Content-Disposition` header, i.e.
+/// everything but the `attr-char`s of RFC 5987.
+const FILENAME: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC
+    .remove(b'!')
+    .remove(b'#')
+    .remove(b'$')
+    .remove(b'&')
+    .remove(b'+')
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'^')
+    .remove(b'_')
+    .remove(b'`')
+    .remove(b'|')
+    .remove(b'~');
+
+/// Builds the `Content-Disposition` header offering a download as `file_name`, following RFC
+/// 6266. Clients which don't understand `filename*` fall back to `filename`, in which every
+/// character that is not printable ASCII or would end the quoted string is replaced by `_`.
+fn content_disposition(file_name: &str) -> String {
+    let fallback = file_name
+        .chars()
+        .map(|c| {
+            if (c.is_ascii_graphic() || c == ' ') && c != '"' && c != '\\' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback,
+        percent_encoding::utf8_percent_encode(file_name, FILENAME)
+    )
+}
+
+/// Characters to escape in the decoded paths of links to the raw endpoint. Slashes are kept to
+/// preserve the path segments.
+const PATH: &percent_encoding::AsciiSet = &percent_encoding::CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// The part of a file to serve for a `Range` header.
+#[derive(Debug, PartialEq)]
+enum ByteRange {
+    /// No or an unsupported range was requested, serve everything.
+    Full,
+    /// Serve the bytes from `start` up to and including `end`.
+    Partial {
+        /// Offset of the first byte.
+        start: usize,
+        /// Offset of the last byte.
+        end: usize,
+    },
+    /// The range lies outside of the content.
+    Unsatisfiable,
+}
+
+/// Matches the value of a `Range` header against content of `len` bytes. Only single byte ranges
+/// are supported, anything else is answered with the full content as permitted by RFC 7233.
+fn byte_range(header: Option<&str>, len: usize) -> ByteRange {
+    let spec = match header.and_then(|header| header.trim().strip_prefix("bytes=")) {
+        Some(spec) if !spec.contains(',') => spec.trim(),
+        _ => return ByteRange::Full,
+    };
+    let (first, last) = match spec.find('-') {
+        Some(idx) => (&spec[..idx], &spec[idx + 1..]),
+        None => return ByteRange::Full,
+    };
+
+    if first.is_empty() {
+        // A suffix range, i.e. the last `n` bytes.
+        return match last.parse::<usize>() {
+            Ok(0) => ByteRange::Unsatisfiable,
+            Ok(_) if len == 0 => ByteRange::Unsatisfiable,
+            Ok(suffix) => ByteRange::Partial {
+                start: len.saturating_sub(suffix),
+                end: len - 1,
+            },
+            Err(_) => ByteRange::Full,
+        };
+    }
+
+    let start = match first.parse::<usize>() {
+        Ok(start) => start,
+        Err(_) => return ByteRange::Full,
+    };
+    let end = if last.is_empty() {
+        len.saturating_sub(1)
+    } else {
+        match last.parse::<usize>() {
+            Ok(end) if end >= start => end.min(len.saturating_sub(1)),
+            _ => return ByteRange::Full,
+        }
+    };
+
+    if start >= len {
+        ByteRange::Unsatisfiable
+    } else {
+        ByteRange::Partial { start, end }
+    }
+}
+
+/// Guesses the MIME type of a file from its extension, falling back to plain text for UTF-8
+/// content and to an opaque byte stream otherwise.
+fn content_type(path: &str, content: &[u8]) -> String {
+    match mime_guess::from_path(path).first() {
+        Some(mime) if mime.type_() == mime_guess::mime::TEXT => {
+            format!("{}; charset=utf-8", mime.essence_str())
+        },
+        Some(mime) => mime.essence_str().to_string(),
+        None if std::str::from_utf8(content).is_ok() => "text/plain; charset=utf-8".to_string(),
+        None => "application/octet-stream".to_string(),
+    }
 }
 
 /// A query param for [`handler::branches`].
@@ -320,12 +1194,30 @@ pub struct TreeQuery {
     revision: Option<coco::Revision<coco::PeerId>>,
 }
 
+/// Bundled query params to pass to the search handler.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    /// PeerId to scope the query by.
+    peer_id: Option<coco::PeerId>,
+    /// Revision to query at.
+    revision: Option<coco::Revision<coco::PeerId>>,
+    /// What to search for.
+    query: coco::source::search::Query,
+    /// Maximum number of matching lines.
+    limit: Option<usize>,
+    /// Number of lines to include around each matching line.
+    context: Option<usize>,
+}
+
 /// A query param for [`handler::tags`].
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TagQuery {
     /// PeerId to scope the query by.
     peer_id: Option<coco::PeerId>,
+    /// Whether to return the [`coco::TagDetails`] of every tag instead of just the names.
+    details: Option<bool>,
 }
 
 #[allow(clippy::non_ascii_literal, clippy::unwrap_used)]
@@ -337,10 +1229,80 @@ mod test {
     use serde_json::{json, Value};
     use warp::{http::StatusCode, test::request};
 
+    use radicle_surf::vcs::git::git2;
+
     use crate::{context, error, http};
 
     #[tokio::test]
-    async fn blob() -> Result<(), Box<dyn std::error::Error>> {
+    #[allow(clippy::indexing_slicing)]
+    async fn archive() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let urn = replicate_platinum(&ctx).await?;
+        let revision = coco::Revision::Tag {
+            name: "v0.1.0".to_string(),
+        };
+
+        for format in &[
+            coco::source::archive::Format::TarGz,
+            coco::source::archive::Format::Zip,
+        ] {
+            let query = super::ArchiveQuery {
+                peer_id: None,
+                revision: Some(revision.clone()),
+                format: Some(*format),
+            };
+            let res = request()
+                .method("GET")
+                .path(&format!(
+                    "/archive/{}?{}",
+                    urn,
+                    serde_qs::to_string(&query).unwrap()
+                ))
+                .reply(&api)
+                .await;
+
+            let default_branch = ctx.state.find_default_branch(urn.clone()).await?;
+            let (want, data) = ctx
+                .state
+                .with_browser_and_repo(default_branch, |browser, repo| {
+                    let archive = coco::archive(
+                        browser,
+                        repo,
+                        Some(revision.clone()),
+                        "git-platinum",
+                        *format,
+                    )?;
+                    let mut data = vec![];
+                    archive.write(repo, &mut data)?;
+                    Ok((archive, data))
+                })
+                .await?;
+
+            assert_eq!(res.status(), StatusCode::OK);
+            assert_eq!(
+                res.headers()["content-type"],
+                format.content_type().to_string()
+            );
+            assert_eq!(
+                res.headers()["content-disposition"],
+                format!(
+                    "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+                    want.file_name, want.file_name
+                )
+            );
+            assert!(want.file_name.starts_with("git-platinum-"));
+            assert_eq!(res.body().to_vec(), data);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn blame() -> Result<(), Box<dyn std::error::Error>> {
         let tmp_dir = tempfile::tempdir()?;
         let ctx = context::Unsealed::tmp(&tmp_dir).await?;
         let api = super::filters(ctx.clone().into());
@@ -351,36 +1313,214 @@ mod test {
             peer_id: None,
         };
         let path = "text/arrows.txt";
+        let query = super::BlameQuery {
+            path: path.to_string(),
+            peer_id: None,
+            revision: Some(revision.clone()),
+        };
+        let res = request()
+            .method("GET")
+            .path(&format!(
+                "/blame/{}?{}",
+                urn,
+                serde_qs::to_string(&query).unwrap()
+            ))
+            .reply(&api)
+            .await;
+
+        let default_branch = ctx.state.find_default_branch(urn).await?;
+        let want = ctx
+            .state
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::blame(browser, repo, Some(revision), path)
+            })
+            .await?;
+
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, json!(want));
+            assert_eq!(have["path"], json!(path));
+            assert_eq!(
+                have["hunks"][0]["commit"]["sha1"],
+                json!("1e0206da8571ca71c51c91154e2fee376e09b4e7")
+            );
+            assert_eq!(
+                have["hunks"][0]["lines"][0],
+                json!({
+                    "number": 1,
+                    "content": "  ;;;;;        ;;;;;        ;;;;;",
+                })
+            );
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn blob_tokens() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let urn = replicate_platinum(&ctx).await?;
+        let revision = coco::Revision::Branch {
+            name: "master".to_string(),
+            peer_id: None,
+        };
+        let path = "src/memory.rs";
+        let lines = coco::source::highlight::LineRange {
+            start: Some(2),
+            end: Some(4),
+        };
         let default_branch = ctx.state.find_default_branch(urn.clone()).await?;
         let want = ctx
             .state
-            .with_browser(default_branch, |mut browser| {
-                coco::blob(&mut browser, Some(revision.clone()), path, None)
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::blob(
+                    browser,
+                    repo,
+                    Some(revision.clone()),
+                    path,
+                    coco::Highlight::Tokens(lines),
+                )
             })
             .await?;
 
         let query = super::BlobQuery {
             path: path.to_string(),
             peer_id: None,
-            revision: Some(revision.clone()),
-            highlight: Some(false),
+            revision: Some(revision),
+            highlight: None,
+            theme: None,
+            tokens: Some(true),
+            lines: Some(lines),
         };
-
         let path = format!("/blob/{}?{}", urn, serde_qs::to_string(&query).unwrap());
-
-        // Get ASCII blob.
         let res = request().method("GET").path(&path).reply(&api).await;
 
         http::test::assert_response(&res, StatusCode::OK, |have| {
             assert_eq!(have, json!(want));
-            assert_eq!(
-                have,
-                json!({
-                    "binary": false,
-                    "html": false,
-                    "content": "  ;;;;;        ;;;;;        ;;;;;
-  ;;;;;        ;;;;;        ;;;;;
-  ;;;;;        ;;;;;        ;;;;;
+            assert_eq!(have["html"], json!(false));
+            let numbers = have["content"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|line| line["number"].clone())
+                .collect::<Vec<_>>();
+            assert_eq!(numbers, vec![json!(2), json!(3), json!(4)]);
+            for line in have["content"].as_array().unwrap() {
+                for token in line["tokens"].as_array().unwrap() {
+                    assert_eq!(token["scopes"][0], json!("source.rust"));
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn blob_highlight() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let urn = replicate_platinum(&ctx).await?;
+        let revision = coco::Revision::Branch {
+            name: "master".to_string(),
+            peer_id: None,
+        };
+        let path = "src/memory.rs";
+        let theme = "InspiredGitHub";
+        let default_branch = ctx.state.find_default_branch(urn.clone()).await?;
+        let want = ctx
+            .state
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::blob(
+                    browser,
+                    repo,
+                    Some(revision.clone()),
+                    path,
+                    coco::Highlight::Html(theme),
+                )
+            })
+            .await?;
+
+        let query = super::BlobQuery {
+            path: path.to_string(),
+            peer_id: None,
+            revision: Some(revision),
+            highlight: Some(true),
+            theme: Some(theme.to_string()),
+            tokens: None,
+            lines: None,
+        };
+        let path = format!("/blob/{}?{}", urn, serde_qs::to_string(&query).unwrap());
+
+        // The second request is served from the cache and has to yield the same HTML.
+        for _ in 0..2 {
+            let res = request().method("GET").path(&path).reply(&api).await;
+
+            http::test::assert_response(&res, StatusCode::OK, |have| {
+                assert_eq!(have, json!(want));
+                assert_eq!(have["html"], json!(true));
+            });
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn blob() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let urn = replicate_platinum(&ctx).await?;
+        let revision = coco::Revision::Branch {
+            name: "master".to_string(),
+            peer_id: None,
+        };
+        let path = "text/arrows.txt";
+        let default_branch = ctx.state.find_default_branch(urn.clone()).await?;
+        let want = ctx
+            .state
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::blob(
+                    browser,
+                    repo,
+                    Some(revision.clone()),
+                    path,
+                    coco::Highlight::None,
+                )
+            })
+            .await?;
+
+        let query = super::BlobQuery {
+            path: path.to_string(),
+            peer_id: None,
+            revision: Some(revision.clone()),
+            highlight: Some(false),
+            theme: None,
+            tokens: None,
+            lines: None,
+        };
+
+        let path = format!("/blob/{}?{}", urn, serde_qs::to_string(&query).unwrap());
+
+        // Get ASCII blob.
+        let res = request().method("GET").path(&path).reply(&api).await;
+
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, json!(want));
+            assert_eq!(
+                have,
+                json!({
+                    "binary": false,
+                    "html": false,
+                    "content": "  ;;;;;        ;;;;;        ;;;;;
+  ;;;;;        ;;;;;        ;;;;;
+  ;;;;;        ;;;;;        ;;;;;
   ;;;;;        ;;;;;        ;;;;;
 ..;;;;;..    ..;;;;;..    ..;;;;;..
  ':::::'      ':::::'      ':::::'
@@ -414,8 +1554,14 @@ mod test {
         let default_branch = ctx.state.find_default_branch(urn.clone()).await?;
         let want = ctx
             .state
-            .with_browser(default_branch, |browser| {
-                coco::blob(browser, Some(revision.clone()), path, None)
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::blob(
+                    browser,
+                    repo,
+                    Some(revision.clone()),
+                    path,
+                    coco::Highlight::None,
+                )
             })
             .await?;
 
@@ -424,6 +1570,9 @@ mod test {
             peer_id: None,
             revision: Some(revision),
             highlight: Some(false),
+            theme: None,
+            tokens: None,
+            lines: None,
         };
 
         let path = format!("/blob/{}?{}", urn, serde_qs::to_string(&query).unwrap());
@@ -464,53 +1613,722 @@ mod test {
     }
 
     #[tokio::test]
-    async fn blob_dev_branch() -> Result<(), Box<dyn std::error::Error>> {
+    async fn blob_dev_branch() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let urn = replicate_platinum(&ctx).await?;
+        let revision = coco::Revision::Branch {
+            name: "dev".to_string(),
+            peer_id: None,
+        };
+        let path = "here-we-are-on-a-dev-branch.lol";
+
+        let query = super::BlobQuery {
+            path: path.to_string(),
+            peer_id: None,
+            revision: Some(revision.clone()),
+            highlight: Some(false),
+            theme: None,
+            tokens: None,
+            lines: None,
+        };
+
+        // Get ASCII blob.
+        let res = request()
+            .method("GET")
+            .path(&format!(
+                "/blob/{}?{}",
+                urn,
+                serde_qs::to_string(&query).unwrap()
+            ))
+            .reply(&api)
+            .await;
+
+        let default_branch = ctx.state.find_default_branch(urn).await?;
+        let want = ctx
+            .state
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::blob(browser, repo, Some(revision), path, coco::Highlight::None)
+            })
+            .await?;
+
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, json!(want));
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn branches() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+        let urn = replicate_platinum(&ctx).await?;
+
+        let res = request()
+            .method("GET")
+            .path(&format!("/branches/{}", urn))
+            .reply(&api)
+            .await;
+
+        let default_branch = ctx.state.find_default_branch(urn).await?;
+        let want = ctx
+            .state
+            .with_browser(default_branch, |browser| coco::branches(browser, None))
+            .await?;
+
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, json!(want));
+            assert_eq!(have, json!(["dev", "master"]));
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn commit() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let owner = ctx.state.init_owner("cloudhead").await?;
+        let project = coco::control::replicate_platinum(
+            &ctx.state,
+            &owner,
+            "git-platinum",
+            "fixture data",
+            coco::control::default_branch(),
+        )
+        .await?;
+        let urn = project.urn();
+        let sha1 = coco::oid::Oid::try_from("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?;
+
+        // The tracked peer's master is the same as ours, tag the commit for both of us.
+        let (remote, _) = coco::control::track_fake_peer(&ctx.state, &project, "rafalca").await;
+        {
+            let monorepo = git2::Repository::open(ctx.state.monorepo())?;
+            let prefix = format!("refs/namespaces/{}/refs", urn.id);
+            monorepo.reference(&format!("{}/tags/docs", prefix), sha1.into(), false, "tag")?;
+            monorepo.reference(
+                &format!("{}/remotes/{}/tags/reviewed", prefix, remote),
+                sha1.into(),
+                false,
+                "tag",
+            )?;
+        }
+
+        let res = request()
+            .method("GET")
+            .path(&format!("/commit/{}/{}", urn, sha1))
+            .reply(&api)
+            .await;
+
+        let default_branch = ctx.state.find_default_branch(urn).await?;
+        let want = ctx
+            .state
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::commit(
+                    browser,
+                    repo,
+                    sha1,
+                    &ctx.state.peer_id(),
+                    coco::source::changeset::DEFAULT_SIMILARITY,
+                )
+            })
+            .await?;
+
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have["header"], json!(want.header));
+            assert_eq!(have["branches"], json!(want.branches));
+            assert_eq!(have["tags"], json!(want.tags));
+
+            // Branches and tags of the local peer come first.
+            let branches = have["branches"].as_array().unwrap();
+            assert!(branches.contains(&json!({ "name": "master", "peerId": null })));
+            assert_eq!(
+                branches.last(),
+                Some(&json!({ "name": "master", "peerId": remote }))
+            );
+            let tags = have["tags"].as_array().unwrap();
+            assert!(tags.contains(&json!({ "name": "docs", "peerId": null })));
+            assert_eq!(
+                tags.last(),
+                Some(&json!({ "name": "reviewed", "peerId": remote }))
+            );
+            assert_eq!(
+                have["header"],
+                json!({
+                    "sha1": sha1,
+                    "author": {
+                        "name": "Fintan Halpenny",
+                        "email": "fintan.halpenny@gmail.com",
+                    },
+                    "committer": {
+                        "email": "noreply@github.com",
+                        "name": "GitHub",
+                    },
+                    "summary": "Extend the docs (#2)",
+                    "description": "I want to have files under src that have separate commits.\r\nThat way src\'s latest commit isn\'t the same as all its files, instead it\'s the file that was touched last.",
+                    "committerTime": 1_578_309_972,
+                    // Signed by GitHub, whose key isn't in the keyring.
+                    "signature": {
+                        "status": "unverified",
+                    },
+                }),
+            );
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn commits() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let urn = replicate_platinum(&ctx).await?;
+
+        let branch_name = "dev";
+        let revision = coco::Revision::Branch {
+            name: branch_name.to_string(),
+            peer_id: None,
+        };
+        let query = super::CommitsQuery {
+            revision: Some(revision.clone()),
+            path: None,
+            after: None,
+            limit: None,
+            author: None,
+            since: None,
+            until: None,
+        };
+        let res = request()
+            .method("GET")
+            .path(&format!(
+                "/commits/{}?{}",
+                urn.clone(),
+                serde_qs::to_string(&query).unwrap(),
+            ))
+            .reply(&api)
+            .await;
+
+        let default_branch = ctx.state.find_default_branch(urn).await?;
+        let want = ctx
+            .state
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::commits(
+                    browser,
+                    repo,
+                    Some(revision.clone()),
+                    &coco::CommitsFilter::default(),
+                )
+            })
+            .await?;
+
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, json!(want));
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn commits_path() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let urn = replicate_platinum(&ctx).await?;
+
+        let revision = coco::Revision::Branch {
+            name: "master".to_string(),
+            peer_id: None,
+        };
+        let filter = coco::CommitsFilter {
+            path: Some("text/arrows.txt".to_string()),
+            limit: Some(1),
+            ..coco::CommitsFilter::default()
+        };
+        let query = super::CommitsQuery {
+            revision: Some(revision.clone()),
+            path: filter.path.clone(),
+            after: None,
+            limit: filter.limit,
+            author: None,
+            since: None,
+            until: None,
+        };
+        let res = request()
+            .method("GET")
+            .path(&format!(
+                "/commits/{}?{}",
+                urn.clone(),
+                serde_qs::to_string(&query).unwrap(),
+            ))
+            .reply(&api)
+            .await;
+
+        let default_branch = ctx.state.find_default_branch(urn).await?;
+        let want = ctx
+            .state
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::commits(browser, repo, Some(revision.clone()), &filter)
+            })
+            .await?;
+
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, json!(want));
+            assert_eq!(
+                have["headers"],
+                json!([{
+                    "sha1": "1e0206da8571ca71c51c91154e2fee376e09b4e7",
+                    "author": {
+                        "name": "Rūdolfs Ošiņš",
+                        "email": "rudolfs@osins.org",
+                    },
+                    "committer": {
+                        "name": "Rūdolfs Ošiņš",
+                        "email": "rudolfs@osins.org",
+                    },
+                    "summary": "Add text files",
+                    "description": "",
+                    "committerTime": 1_575_283_425,
+                }])
+            );
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn commits_page() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let urn = replicate_platinum(&ctx).await?;
+
+        let revision = coco::Revision::Branch {
+            name: "master".to_string(),
+            peer_id: None,
+        };
+        let default_branch = ctx.state.find_default_branch(urn.clone()).await?;
+        let (first_page, all) = ctx
+            .state
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                let filter = coco::CommitsFilter {
+                    limit: Some(2),
+                    ..coco::CommitsFilter::default()
+                };
+                let first_page = coco::commits(browser, repo, Some(revision.clone()), &filter)?;
+                let all = coco::commits(
+                    browser,
+                    repo,
+                    Some(revision.clone()),
+                    &coco::CommitsFilter::default(),
+                )?;
+                Ok((first_page, all))
+            })
+            .await?;
+        let cursor = first_page.next.expect("there should be a next page");
+        assert_eq!(cursor, all.headers[1].sha1);
+
+        let query = super::CommitsQuery {
+            revision: Some(revision.clone()),
+            path: None,
+            after: Some(cursor),
+            limit: Some(2),
+            author: Some("RUDOLFS@osins.org".to_string()),
+            since: None,
+            until: None,
+        };
+        let res = request()
+            .method("GET")
+            .path(&format!(
+                "/commits/{}?{}",
+                urn,
+                serde_qs::to_string(&query).unwrap(),
+            ))
+            .reply(&api)
+            .await;
+
+        let want = all
+            .headers
+            .iter()
+            .skip(2)
+            .filter(|header| header.author.email == "rudolfs@osins.org")
+            .take(2)
+            .collect::<Vec<_>>();
+
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have["headers"], json!(want));
+        });
+
+        let query = super::CommitsQuery {
+            after: Some("0101010101010101010101010101010101010101".parse()?),
+            ..query
+        };
+        let res = request()
+            .method("GET")
+            .path(&format!(
+                "/commits/{}?{}",
+                urn,
+                serde_qs::to_string(&query).unwrap(),
+            ))
+            .reply(&api)
+            .await;
+
+        http::test::assert_response(&res, StatusCode::BAD_REQUEST, |have| {
+            assert_eq!(have["variant"], "UNKNOWN_CURSOR");
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn commits_stats() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let urn = replicate_platinum(&ctx).await?;
+
+        let revision = coco::Revision::Branch {
+            name: "dev".to_string(),
+            peer_id: None,
+        };
+        let query = super::CommitsStatsQuery {
+            revision: Some(revision.clone()),
+        };
+        let res = request()
+            .method("GET")
+            .path(&format!(
+                "/commits/{}/stats?{}",
+                urn,
+                serde_qs::to_string(&query).unwrap(),
+            ))
+            .reply(&api)
+            .await;
+
+        let default_branch = ctx.state.find_default_branch(urn).await?;
+        let want = ctx
+            .state
+            .with_browser(default_branch, |browser| {
+                coco::commits_stats(browser, Some(revision))
+            })
+            .await?;
+
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, json!(want));
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn compare() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let urn = replicate_platinum(&ctx).await?;
+
+        let base = coco::Revision::Branch {
+            name: "master".to_string(),
+            peer_id: None,
+        };
+        let head = coco::Revision::Branch {
+            name: "dev".to_string(),
+            peer_id: None,
+        };
+        let query = super::CompareQuery {
+            base: base.clone(),
+            head: head.clone(),
+            similarity: None,
+        };
+        let res = request()
+            .method("GET")
+            .path(&format!(
+                "/compare/{}?{}",
+                urn,
+                serde_qs::to_string(&query).unwrap()
+            ))
+            .reply(&api)
+            .await;
+
+        let default_branch = ctx.state.find_default_branch(urn).await?;
+        let want = ctx
+            .state
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::compare(
+                    browser,
+                    repo,
+                    base,
+                    head,
+                    coco::source::changeset::DEFAULT_SIMILARITY,
+                )
+            })
+            .await?;
+
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, json!(want));
+            assert!(!have["commits"].as_array().unwrap().is_empty());
+            assert!(have["files"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|file| file["path"] == json!("here-we-are-on-a-dev-branch.lol")));
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn commits_invalid_limit() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let urn = replicate_platinum(&ctx).await?;
+
+        let query = super::CommitsQuery {
+            revision: None,
+            path: None,
+            after: None,
+            limit: Some(0),
+            author: None,
+            since: None,
+            until: None,
+        };
+        let res = request()
+            .method("GET")
+            .path(&format!(
+                "/commits/{}?{}",
+                urn,
+                serde_qs::to_string(&query).unwrap()
+            ))
+            .reply(&api)
+            .await;
+
+        http::test::assert_response(&res, StatusCode::BAD_REQUEST, |have| {
+            assert_eq!(
+                have,
+                json!({
+                    "message": "the limit must be at least 1",
+                    "variant": "INVALID_LIMIT",
+                })
+            );
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn compare_invalid_similarity() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let urn = replicate_platinum(&ctx).await?;
+
+        let query = super::CompareQuery {
+            base: coco::Revision::Branch {
+                name: "master".to_string(),
+                peer_id: None,
+            },
+            head: coco::Revision::Branch {
+                name: "dev".to_string(),
+                peer_id: None,
+            },
+            similarity: Some(101),
+        };
+        let res = request()
+            .method("GET")
+            .path(&format!(
+                "/compare/{}?{}",
+                urn,
+                serde_qs::to_string(&query).unwrap()
+            ))
+            .reply(&api)
+            .await;
+
+        http::test::assert_response(&res, StatusCode::BAD_REQUEST, |have| {
+            assert_eq!(
+                have,
+                json!({
+                    "message": "the similarity 101 is not a percentage between 0 and 100",
+                    "variant": "INVALID_SIMILARITY",
+                })
+            );
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn local_state() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let mut path = env::current_dir()?;
+        path.push("../../fixtures/git-platinum");
+
+        let res = request()
+            .method("GET")
+            .path(&format!("/local-state/{}", path.to_str().unwrap()))
+            .reply(&api)
+            .await;
+
+        let want = coco::local_state(path.to_str().unwrap()).unwrap();
+
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, json!(want));
+            assert_eq!(
+                have,
+                json!({
+                    "branches": [
+                        "dev",
+                        "master",
+                    ],
+                }),
+            );
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn contributors() -> Result<(), Box<dyn std::error::Error>> {
         let tmp_dir = tempfile::tempdir()?;
         let ctx = context::Unsealed::tmp(&tmp_dir).await?;
         let api = super::filters(ctx.clone().into());
+        let urn = replicate_platinum(&ctx).await?;
+
+        let default_branch = ctx.state.find_default_branch(urn.clone()).await?;
+        let want = ctx
+            .state
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::contributors(browser, repo, None::<coco::Revision<coco::PeerId>>)
+            })
+            .await?;
+
+        // The second request is served from the store.
+        for _ in 0..2 {
+            let res = request()
+                .method("GET")
+                .path(&format!("/contributors/{}", urn))
+                .reply(&api)
+                .await;
+
+            http::test::assert_response(&res, StatusCode::OK, |have| {
+                assert_eq!(have, json!(want));
+                let contributor = &have.as_array().unwrap()[0];
+                let weekly = contributor["weeks"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|week| week["commits"].as_u64().unwrap())
+                    .sum::<u64>();
+                assert_eq!(contributor["commits"].as_u64().unwrap(), weekly);
+            });
+        }
+
+        Ok(())
+    }
 
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn graph() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
         let urn = replicate_platinum(&ctx).await?;
-        let revision = coco::Revision::Branch {
-            name: "dev".to_string(),
-            peer_id: None,
-        };
-        let path = "here-we-are-on-a-dev-branch.lol";
 
-        let query = super::BlobQuery {
-            path: path.to_string(),
-            peer_id: None,
-            revision: Some(revision.clone()),
-            highlight: Some(false),
+        let revisions = vec![
+            coco::Revision::Branch {
+                name: "master".to_string(),
+                peer_id: None,
+            },
+            coco::Revision::Branch {
+                name: "dev".to_string(),
+                peer_id: None,
+            },
+        ];
+        let query = super::GraphQuery {
+            revisions: Some(revisions.clone()),
+            limit: None,
         };
-
-        // Get ASCII blob.
         let res = request()
             .method("GET")
             .path(&format!(
-                "/blob/{}?{}",
+                "/graph/{}?{}",
                 urn,
                 serde_qs::to_string(&query).unwrap()
             ))
             .reply(&api)
             .await;
 
+        let default_branch = ctx.state.find_default_branch(urn.clone()).await?;
+        let master = ctx
+            .state
+            .with_browser(default_branch, |browser| {
+                Ok(coco::oid::Oid::from(browser.get().first().id))
+            })
+            .await?;
         let default_branch = ctx.state.find_default_branch(urn).await?;
         let want = ctx
             .state
-            .with_browser(default_branch, |mut browser| {
-                coco::blob(&mut browser, Some(revision), path, None)
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::graph(browser, repo, revisions, None)
             })
             .await?;
 
         http::test::assert_response(&res, StatusCode::OK, |have| {
             assert_eq!(have, json!(want));
+
+            let nodes = have["nodes"].as_array().unwrap();
+            let labelled = |name: &str| {
+                nodes
+                    .iter()
+                    .filter(|node| {
+                        node["branches"]
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .any(|branch| branch["name"] == json!(name))
+                    })
+                    .map(|node| node["header"]["sha1"].clone())
+                    .collect::<Vec<_>>()
+            };
+            // Branches only label their tips.
+            assert_eq!(labelled("master"), vec![json!(master)]);
+            assert_eq!(labelled("dev").len(), 1);
+            // Every parent is listed after its child.
+            for (idx, node) in nodes.iter().enumerate() {
+                for parent in node["parents"].as_array().unwrap() {
+                    assert!(!nodes[..idx]
+                        .iter()
+                        .any(|earlier| earlier["header"]["sha1"] == parent["sha1"]));
+                }
+            }
         });
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn branches() -> Result<(), Box<dyn std::error::Error>> {
+    #[allow(clippy::indexing_slicing)]
+    async fn languages() -> Result<(), Box<dyn std::error::Error>> {
         let tmp_dir = tempfile::tempdir()?;
         let ctx = context::Unsealed::tmp(&tmp_dir).await?;
         let api = super::filters(ctx.clone().into());
@@ -518,19 +2336,35 @@ mod test {
 
         let res = request()
             .method("GET")
-            .path(&format!("/branches/{}", urn))
+            .path(&format!("/languages/{}", urn))
             .reply(&api)
             .await;
 
         let default_branch = ctx.state.find_default_branch(urn).await?;
         let want = ctx
             .state
-            .with_browser(default_branch, |browser| coco::branches(browser, None))
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::languages(browser, repo, None::<coco::Revision<coco::PeerId>>)
+            })
             .await?;
 
         http::test::assert_response(&res, StatusCode::OK, |have| {
             assert_eq!(have, json!(want));
-            assert_eq!(have, json!(["dev", "master"]));
+            let mut names = have
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|language| language["name"].as_str().unwrap())
+                .collect::<Vec<_>>();
+            names.sort_unstable();
+            assert_eq!(names, vec!["Haskell", "Markdown", "Rust"]);
+            let total = have
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|language| language["percentage"].as_f64().unwrap())
+                .sum::<f64>();
+            assert!((total - 100.0).abs() < 0.001);
         });
 
         Ok(())
@@ -538,46 +2372,207 @@ mod test {
 
     #[tokio::test]
     #[allow(clippy::indexing_slicing)]
-    async fn commit() -> Result<(), Box<dyn std::error::Error>> {
+    async fn raw() -> Result<(), Box<dyn std::error::Error>> {
         let tmp_dir = tempfile::tempdir()?;
         let ctx = context::Unsealed::tmp(&tmp_dir).await?;
         let api = super::filters(ctx.clone().into());
+        let urn = replicate_platinum(&ctx).await?;
+
+        let path = "text/arrows.txt";
+        let default_branch = ctx.state.find_default_branch(urn.clone()).await?;
+        let want = ctx
+            .state
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::raw(
+                    browser,
+                    repo,
+                    None::<coco::Revision<coco::PeerId>>,
+                    path,
+                    <[u8]>::to_vec,
+                )
+            })
+            .await?;
+
+        let res = request()
+            .method("GET")
+            .path(&format!("/raw/{}/{}", urn, path))
+            .reply(&api)
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()["content-type"], "text/plain; charset=utf-8");
+        assert_eq!(res.headers()["content-length"], want.len().to_string());
+        assert_eq!(res.body().to_vec(), want);
+
+        let res = request()
+            .method("GET")
+            .path(&format!("/raw/{}/{}", urn, path))
+            .header("range", "bytes=2-8")
+            .reply(&api)
+            .await;
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            res.headers()["content-range"],
+            format!("bytes 2-8/{}", want.len())
+        );
+        assert_eq!(res.body().as_ref(), b";;;;;  ");
+
+        let res = request()
+            .method("GET")
+            .path(&format!("/raw/{}/{}", urn, path))
+            .header("range", format!("bytes={}-", want.len()))
+            .reply(&api)
+            .await;
+        assert_eq!(res.status(), StatusCode::RANGE_NOT_SATISFIABLE);
 
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn readme() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
         let urn = replicate_platinum(&ctx).await?;
-        let sha1 = coco::oid::Oid::try_from("3873745c8f6ffb45c990eb23b491d4b4b6182f95")?;
 
         let res = request()
             .method("GET")
-            .path(&format!("/commit/{}/{}", urn, sha1))
+            .path(&format!("/readme/{}", urn))
+            .reply(&api)
+            .await;
+
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have["path"], json!("README.md"));
+            let html = have["html"].as_str().unwrap();
+            assert!(html.starts_with("<p>"));
+            assert!(!html.contains("<script"));
+        });
+
+        let query = super::ReadmeQuery {
+            peer_id: None,
+            revision: None,
+            prefix: Some("text".to_string()),
+        };
+        let res = request()
+            .method("GET")
+            .path(&format!(
+                "/readme/{}?{}",
+                urn,
+                serde_qs::to_string(&query).unwrap()
+            ))
             .reply(&api)
             .await;
 
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, Value::Null);
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn content_disposition() {
+        use super::content_disposition;
+
+        assert_eq!(
+            content_disposition("git-platinum-a0b1c2d.tar.gz"),
+            "attachment; filename=\"git-platinum-a0b1c2d.tar.gz\"; \
+             filename*=UTF-8''git-platinum-a0b1c2d.tar.gz"
+        );
+        assert_eq!(
+            content_disposition("my \"übersicht\"\r\n.zip"),
+            "attachment; filename=\"my __bersicht___.zip\"; \
+             filename*=UTF-8''my%20%22%C3%BCbersicht%22%0D%0A.zip"
+        );
+    }
+
+    #[test]
+    fn byte_range() {
+        use super::{byte_range, ByteRange};
+
+        assert_eq!(byte_range(None, 10), ByteRange::Full);
+        assert_eq!(byte_range(Some("bytes=0-1,4-5"), 10), ByteRange::Full);
+        assert_eq!(byte_range(Some("lines=1-2"), 10), ByteRange::Full);
+        assert_eq!(byte_range(Some("bytes=5-2"), 10), ByteRange::Full);
+        assert_eq!(
+            byte_range(Some("bytes=2-4"), 10),
+            ByteRange::Partial { start: 2, end: 4 }
+        );
+        assert_eq!(
+            byte_range(Some("bytes=2-"), 10),
+            ByteRange::Partial { start: 2, end: 9 }
+        );
+        assert_eq!(
+            byte_range(Some("bytes=8-20"), 10),
+            ByteRange::Partial { start: 8, end: 9 }
+        );
+        assert_eq!(
+            byte_range(Some("bytes=-3"), 10),
+            ByteRange::Partial { start: 7, end: 9 }
+        );
+        assert_eq!(byte_range(Some("bytes=10-"), 10), ByteRange::Unsatisfiable);
+        assert_eq!(byte_range(Some("bytes=-0"), 10), ByteRange::Unsatisfiable);
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn search() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+        let urn = replicate_platinum(&ctx).await?;
+
+        let revision = coco::Revision::Branch {
+            name: "master".to_string(),
+            peer_id: None,
+        };
+        let query = super::SearchQuery {
+            peer_id: None,
+            revision: Some(revision.clone()),
+            query: coco::source::search::Query::Literal {
+                text: ";;;;;..".to_string(),
+            },
+            limit: Some(1),
+            context: Some(1),
+        };
+        let path = format!("/search/{}?{}", urn, serde_qs::to_string(&query).unwrap());
+        let res = request().method("GET").path(&path).reply(&api).await;
+
         let default_branch = ctx.state.find_default_branch(urn).await?;
         let want = ctx
             .state
-            .with_browser(default_branch, |mut browser| {
-                coco::commit_header(&mut browser, sha1)
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::search(
+                    browser,
+                    repo,
+                    Some(revision),
+                    &query.query,
+                    coco::source::search::Options {
+                        limit: 1,
+                        context: 1,
+                    },
+                )
             })
             .await?;
 
         http::test::assert_response(&res, StatusCode::OK, |have| {
-            assert_eq!(have["header"], json!(want));
+            assert_eq!(have, json!(want));
             assert_eq!(
-                have["header"],
+                have["files"][0],
                 json!({
-                    "sha1": sha1,
-                    "author": {
-                        "name": "Fintan Halpenny",
-                        "email": "fintan.halpenny@gmail.com",
-                    },
-                    "committer": {
-                        "email": "noreply@github.com",
-                        "name": "GitHub",
-                    },
-                    "summary": "Extend the docs (#2)",
-                    "description": "I want to have files under src that have separate commits.\r\nThat way src\'s latest commit isn\'t the same as all its files, instead it\'s the file that was touched last.",
-                    "committerTime": 1_578_309_972,
-                }),
+                    "path": "text/arrows.txt",
+                    "lines": [{
+                        "number": 5,
+                        "content": "..;;;;;..    ..;;;;;..    ..;;;;;..",
+                        "matches": [
+                            { "start": 2, "end": 9 },
+                            { "start": 15, "end": 22 },
+                            { "start": 28, "end": 35 },
+                        ],
+                        "before": ["  ;;;;;        ;;;;;        ;;;;;"],
+                        "after": [" ':::::'      ':::::'      ':::::'"],
+                    }],
+                })
             );
         });
 
@@ -585,81 +2580,102 @@ mod test {
     }
 
     #[tokio::test]
-    async fn commits() -> Result<(), Box<dyn std::error::Error>> {
+    async fn search_invalid_regex() -> Result<(), Box<dyn std::error::Error>> {
         let tmp_dir = tempfile::tempdir()?;
         let ctx = context::Unsealed::tmp(&tmp_dir).await?;
         let api = super::filters(ctx.clone().into());
-
         let urn = replicate_platinum(&ctx).await?;
 
-        let branch_name = "dev";
-        let revision = coco::Revision::Branch {
-            name: branch_name.to_string(),
+        let query = super::SearchQuery {
             peer_id: None,
+            revision: None,
+            query: coco::source::search::Query::Regex {
+                expression: "(unclosed".to_string(),
+            },
+            limit: None,
+            context: None,
         };
-        let query = super::CommitsQuery {
-            revision: Some(revision.clone()),
-        };
+        let path = format!("/search/{}?{}", urn, serde_qs::to_string(&query).unwrap());
+        let res = request().method("GET").path(&path).reply(&api).await;
+
+        http::test::assert_response(&res, StatusCode::BAD_REQUEST, |have| {
+            assert_eq!(have["variant"], json!("INVALID_SEARCH_QUERY"));
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tags() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let ctx = context::Unsealed::tmp(&tmp_dir).await?;
+        let api = super::filters(ctx.clone().into());
+
+        let urn = replicate_platinum(&ctx).await?;
+
         let res = request()
             .method("GET")
-            .path(&format!(
-                "/commits/{}?{}",
-                urn.clone(),
-                serde_qs::to_string(&query).unwrap(),
-            ))
+            .path(&format!("/tags/{}", urn))
             .reply(&api)
             .await;
 
         let default_branch = ctx.state.find_default_branch(urn).await?;
         let want = ctx
             .state
-            .with_browser(default_branch, |mut browser| {
-                coco::commits(&mut browser, Some(revision.clone()))
-            })
+            .with_browser(default_branch, |browser| coco::tags(browser))
             .await?;
-
         http::test::assert_response(&res, StatusCode::OK, |have| {
             assert_eq!(have, json!(want));
+            assert_eq!(
+                have,
+                json!(["v0.1.0", "v0.2.0", "v0.3.0", "v0.4.0", "v0.5.0"]),
+            );
         });
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn local_state() -> Result<(), Box<dyn std::error::Error>> {
+    #[allow(clippy::indexing_slicing)]
+    async fn tags_details() -> Result<(), Box<dyn std::error::Error>> {
         let tmp_dir = tempfile::tempdir()?;
         let ctx = context::Unsealed::tmp(&tmp_dir).await?;
         let api = super::filters(ctx.clone().into());
 
-        let mut path = env::current_dir()?;
-        path.push("../../fixtures/git-platinum");
+        let urn = replicate_platinum(&ctx).await?;
 
         let res = request()
             .method("GET")
-            .path(&format!("/local-state/{}", path.to_str().unwrap()))
+            .path(&format!("/tags/{}?details=true", urn))
             .reply(&api)
             .await;
 
-        let want = coco::local_state(path.to_str().unwrap()).unwrap();
-
+        let default_branch = ctx.state.find_default_branch(urn).await?;
+        let want = ctx
+            .state
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::tags_details(browser, repo)
+            })
+            .await?;
         http::test::assert_response(&res, StatusCode::OK, |have| {
             assert_eq!(have, json!(want));
-            assert_eq!(
-                have,
-                json!({
-                    "branches": [
-                        "dev",
-                        "master",
-                    ],
-                }),
-            );
+            let tags = have.as_array().unwrap();
+            assert_eq!(tags.len(), 5);
+            for tag in tags {
+                assert!(tag["target"]["sha1"].is_string());
+                assert_eq!(
+                    tag["annotated"].as_bool().unwrap(),
+                    tag["message"].is_string()
+                );
+            }
         });
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn tags() -> Result<(), Box<dyn std::error::Error>> {
+    #[allow(clippy::indexing_slicing)]
+    async fn tag() -> Result<(), Box<dyn std::error::Error>> {
         let tmp_dir = tempfile::tempdir()?;
         let ctx = context::Unsealed::tmp(&tmp_dir).await?;
         let api = super::filters(ctx.clone().into());
@@ -668,27 +2684,49 @@ mod test {
 
         let res = request()
             .method("GET")
-            .path(&format!("/tags/{}", urn))
+            .path(&format!("/tag/{}/v0.3.0", urn))
             .reply(&api)
             .await;
 
-        let default_branch = ctx.state.find_default_branch(urn).await?;
+        let default_branch = ctx.state.find_default_branch(urn.clone()).await?;
         let want = ctx
             .state
-            .with_browser(default_branch, |browser| coco::tags(browser))
+            .with_browser_and_repo(default_branch, |_browser, repo| coco::tag(repo, "v0.3.0"))
             .await?;
         http::test::assert_response(&res, StatusCode::OK, |have| {
             assert_eq!(have, json!(want));
-            assert_eq!(
-                have,
-                json!(["v0.1.0", "v0.2.0", "v0.3.0", "v0.4.0", "v0.5.0"]),
-            );
+            assert_eq!(have["name"], json!("v0.3.0"));
         });
 
+        let res = request()
+            .method("GET")
+            .path(&format!("/tag/{}/v9.9.9", urn))
+            .reply(&api)
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
         Ok(())
     }
 
     #[tokio::test]
+    async fn themes() {
+        let res = request()
+            .method("GET")
+            .path("/themes")
+            .reply(&super::themes_filter())
+            .await;
+
+        http::test::assert_response(&res, StatusCode::OK, |have| {
+            assert_eq!(have, json!(coco::source::highlight::themes()));
+            assert!(have
+                .as_array()
+                .unwrap()
+                .contains(&json!("base16-ocean.dark")));
+        });
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
     async fn tree() -> Result<(), Box<dyn std::error::Error>> {
         let tmp_dir = tempfile::tempdir()?;
         let ctx = context::Unsealed::tmp(&tmp_dir).await?;
@@ -711,40 +2749,47 @@ mod test {
         let default_branch = ctx.state.find_default_branch(urn).await?;
         let want = ctx
             .state
-            .with_browser(default_branch, |mut browser| {
-                coco::tree(&mut browser, Some(revision), Some(prefix.to_string()))
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::tree(browser, repo, Some(revision), Some(prefix.to_string()))
             })
             .await?;
 
         http::test::assert_response(&res, StatusCode::OK, |have| {
             assert_eq!(have, json!(want));
+            assert_eq!(have["path"], json!("src"));
+            assert_eq!(have["info"]["name"], json!("src"));
+            assert_eq!(have["info"]["objectType"], json!("TREE"));
+
+            let entries = have["entries"].as_array().unwrap();
             assert_eq!(
-                have,
-                json!({
-                    "path": "src",
-                    "info": {
-                        "name": "src",
-                        "objectType": "TREE",
-                        "lastCommit": null,                },
-                        "entries": [
-                        {
-                            "path": "src/Eval.hs",
-                            "info": {
-                                "name": "Eval.hs",
-                                "objectType": "BLOB",
-                                "lastCommit": null,
-                            },
-                        },
-                        {
-                            "path": "src/memory.rs",
-                            "info": {
-                                "name": "memory.rs",
-                                "objectType": "BLOB",
-                                "lastCommit": null,
-                            },
-                        },
-                    ],
-                }),
+                entries
+                    .iter()
+                    .map(|entry| (entry["path"].clone(), entry["info"]["objectType"].clone()))
+                    .collect::<Vec<_>>(),
+                vec![
+                    (json!("src/Eval.hs"), json!("BLOB")),
+                    (json!("src/memory.rs"), json!("BLOB")),
+                ],
+            );
+
+            // Every entry carries the last commit which touched it, the most recent of which is
+            // the last commit of the directory itself.
+            let mut times = entries
+                .iter()
+                .map(|entry| {
+                    entry["info"]["lastCommit"]["committerTime"]
+                        .as_i64()
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+            times.sort_unstable();
+            assert_eq!(
+                Some(
+                    &have["info"]["lastCommit"]["committerTime"]
+                        .as_i64()
+                        .unwrap()
+                ),
+                times.last(),
             );
         });
 
@@ -785,8 +2830,8 @@ mod test {
         let default_branch = ctx.state.find_default_branch(urn).await?;
         let want = ctx
             .state
-            .with_browser(default_branch, |mut browser| {
-                coco::tree(&mut browser, Some(revision), None)
+            .with_browser_and_repo(default_branch, |browser, repo| {
+                coco::tree(browser, repo, Some(revision), None)
             })
             .await?;
 
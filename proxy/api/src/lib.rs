@@ -28,6 +28,7 @@
 
 mod config;
 mod context;
+mod contributors;
 pub mod env;
 mod error;
 mod http;
@@ -35,6 +36,7 @@ mod identity;
 mod notification;
 mod process;
 mod project;
+mod search;
 mod service;
 mod session;
 
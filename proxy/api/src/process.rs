@@ -7,12 +7,12 @@ use std::{future::Future, net, sync::Arc, time::Duration};
 use thiserror::Error;
 use tokio::{
     signal::unix::{signal, SignalKind},
-    sync::{watch, RwLock},
+    sync::{broadcast, watch, RwLock},
 };
 
 use coco::{convert::MaybeFrom as _, peer::run_config, seed, signer, Peer, RunConfig};
 
-use crate::{config, context, http, notification, service, session};
+use crate::{config, context, http, notification, search, service, session};
 
 /// Flags accepted by the proxy binary.
 #[derive(Clone, FromArgs)]
@@ -57,6 +57,29 @@ pub async fn run(args: Args) -> Result<(), Box<dyn std::error::Error>> {
     let bin_dir = config::bin_dir()?;
     coco::git_helper::setup(&proxy_path, &bin_dir)?;
 
+    let highlighting_dir = config::highlighting_dir();
+    if highlighting_dir.is_dir() {
+        if let Err(err) = coco::source::highlight::load_from_folder(&highlighting_dir) {
+            log::warn!(
+                "Failed to load highlighting assets from {}: {}",
+                highlighting_dir.display(),
+                err
+            );
+        }
+    }
+
+    let keyring_path = config::keyring_path();
+    if keyring_path.is_file() {
+        if let Err(err) = coco::source::signature::load_keyring(&keyring_path, config::gnupg_dir())
+        {
+            log::warn!(
+                "Failed to load OpenPGP keyring from {}: {}",
+                keyring_path.display(),
+                err
+            );
+        }
+    }
+
     let mut service_manager = service::Manager::new(args.test)?;
     let mut sighup = signal(SignalKind::hangup())?;
 
@@ -136,6 +159,7 @@ async fn run_rigging(
     let subscriptions = notification::Subscriptions::default();
     let peer_subscriptions = subscriptions.clone();
     let server_ctx = ctx.clone();
+    let search_ctx = ctx.clone();
 
     let server = async move {
         log::info!("starting API");
@@ -204,6 +228,33 @@ async fn run_rigging(
         });
         tasks.push(peer_event_task.map_err(RunError::from).boxed());
 
+        if let context::Context::Unsealed(search_ctx) = search_ctx {
+            let search_task = coco::SpawnAbortable::new({
+                let mut peer_events = peer.subscribe();
+
+                async move {
+                    let context::Unsealed { state, store, .. } = search_ctx;
+                    if let Err(err) = search::index_all(&state, &store).await {
+                        log::error!("Failed to build the search index: {}", err);
+                    }
+
+                    loop {
+                        match peer_events.recv().await {
+                            Ok(event) => search::handle_event(&state, &store, event).await,
+                            // Missed some events, catch up by checking every project.
+                            Err(broadcast::RecvError::Lagged(_)) => {
+                                if let Err(err) = search::index_all(&state, &store).await {
+                                    log::error!("Failed to update the search index: {}", err);
+                                }
+                            },
+                            Err(broadcast::RecvError::Closed) => break,
+                        }
+                    }
+                }
+            });
+            tasks.push(search_task.map_err(RunError::from).boxed());
+        }
+
         let peer = async move {
             log::info!("starting peer");
             peer.into_running().await
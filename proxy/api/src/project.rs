@@ -6,6 +6,7 @@ use std::{collections::HashSet, ops::Deref};
 use serde::{Deserialize, Serialize};
 
 use coco::project::peer;
+use radicle_surf::vcs::git;
 
 use crate::{error, identity};
 
@@ -21,6 +22,9 @@ pub struct Metadata {
     pub default_branch: String,
     /// List of maintainers.
     pub maintainers: HashSet<coco::Urn>,
+    /// The project this one was forked from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<coco::Urn>,
 }
 
 impl<ST> From<coco::Project<ST>> for Metadata
@@ -36,6 +40,7 @@ where
                 .unwrap_or_else(|| "".into()),
             default_branch: project_meta.default_branch().to_string(),
             maintainers: project_meta.maintainers().clone(),
+            upstream: coco::project::fork::upstream(&project_meta),
         }
     }
 }
@@ -56,16 +61,62 @@ pub struct Project<S> {
     pub stats: S,
 }
 
-/// A `Partial` project is one where we _weren't_ able to fetch the [`coco::Stats`] for it.
+/// High-level statistics about the default branch of a project.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    /// Number of branches, commits and contributors.
+    #[serde(flatten)]
+    pub repo: coco::Stats,
+    /// Breakdown of the languages the files are written in, left out in lists of projects.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub languages: Option<Vec<coco::source::languages::Language>>,
+}
+
+impl Stats {
+    /// Computes the [`Stats`] for the revision the `browser` points at.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`coco::source::Error`] if a surf or git interaction fails.
+    pub fn compute(
+        browser: &mut git::Browser<'_>,
+        repo: &git::git2::Repository,
+    ) -> Result<Self, coco::source::Error> {
+        Ok(Self {
+            repo: browser.get_stats()?,
+            languages: Some(coco::languages(
+                browser,
+                repo,
+                None::<coco::Revision<coco::PeerId>>,
+            )?),
+        })
+    }
+
+    /// Computes the [`Stats`] for the revision the `browser` points at, like [`Stats::compute`],
+    /// but without the languages, which are too expensive to compute for every project of a list.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`coco::source::Error`] if a surf interaction fails.
+    pub fn summarise(browser: &mut git::Browser<'_>) -> Result<Self, coco::source::Error> {
+        Ok(Self {
+            repo: browser.get_stats()?,
+            languages: None,
+        })
+    }
+}
+
+/// A `Partial` project is one where we _weren't_ able to fetch the [`Stats`] for it.
 pub type Partial = Project<()>;
 
-/// A `Full` project is one where we _were_ able to fetch the [`coco::Stats`] for it.
-pub type Full = Project<coco::Stats>;
+/// A `Full` project is one where we _were_ able to fetch the [`Stats`] for it.
+pub type Full = Project<Stats>;
 
 impl Partial {
     /// Convert a `Partial` project into a `Full` one by providing the `stats` for the project.
     #[allow(clippy::missing_const_for_fn)]
-    pub fn fulfill(self, stats: coco::Stats) -> Full {
+    pub fn fulfill(self, stats: Stats) -> Full {
         Project {
             urn: self.urn,
             shareable_entity_identifier: self.shareable_entity_identifier,
@@ -95,13 +146,13 @@ where
 }
 
 /// Construct a Project from its metadata and stats
-impl<ST> From<(coco::Project<ST>, coco::Stats)> for Full
+impl<ST> From<(coco::Project<ST>, Stats)> for Full
 where
     ST: Clone,
 {
-    /// Create a `Project` given a [`coco::Project`] and the [`coco::Stats`]
+    /// Create a `Project` given a [`coco::Project`] and the [`Stats`]
     /// for the repository.
-    fn from((project, stats): (coco::Project<ST>, coco::Stats)) -> Self {
+    fn from((project, stats): (coco::Project<ST>, Stats)) -> Self {
         let urn = project.urn();
 
         Self {
@@ -179,6 +230,10 @@ pub struct Projects {
 
     /// A project that failed partially when trying to retrieve metadata for it.
     pub failures: Vec<Failure>,
+
+    /// A project the user archived. It is kept on the device, but neither announced nor synced,
+    /// and left out of the tracked and contributed projects.
+    pub archived: Vec<Full>,
 }
 
 impl Projects {
@@ -192,12 +247,15 @@ impl Projects {
     ///   * We couldn't get the list of projects
     ///   * We couldn't inspect the `signed_refs` of the project
     ///   * We couldn't get stats for a project
-    pub async fn list(state: &coco::State) -> Result<Self, error::Error> {
+    ///   * We couldn't access the archived projects in the `store`
+    pub async fn list(state: &coco::State, store: &kv::Store) -> Result<Self, error::Error> {
         let mut projects = Self {
             tracked: vec![],
             contributed: vec![],
             failures: vec![],
+            archived: vec![],
         };
+        let archived = coco::project::archive::list(store)?;
 
         for project in state.list_projects().await? {
             let project = Project::from(project);
@@ -210,10 +268,7 @@ impl Projects {
                 Ok(branch) => branch,
             };
 
-            let stats = match state
-                .with_browser(default_branch, |browser| Ok(browser.get_stats()?))
-                .await
-            {
+            let stats = match state.with_browser(default_branch, Stats::summarise).await {
                 Err(err) => {
                     log::warn!("Failure for '{}': {}", project.urn, err);
                     projects.failures.push(Failure::Stats(project));
@@ -224,6 +279,11 @@ impl Projects {
 
             let project = project.fulfill(stats);
 
+            if archived.iter().any(|urn| urn.id == project.urn.id) {
+                projects.archived.push(project);
+                continue;
+            }
+
             let refs = match state.list_owner_project_refs(project.urn.clone()).await {
                 Err(err) => {
                     log::warn!("Failure for '{}': {}", project.urn, err);
@@ -312,9 +372,7 @@ impl Iterator for IntoIter {
 pub async fn get(state: &coco::State, project_urn: coco::Urn) -> Result<Full, error::Error> {
     let project = state.get_project(project_urn.clone(), None).await?;
     let branch = state.find_default_branch(project_urn.clone()).await?;
-    let project_stats = state
-        .with_browser(branch, |browser| Ok(browser.get_stats()?))
-        .await?;
+    let project_stats = state.with_browser_and_repo(branch, Stats::compute).await?;
 
     Ok((project, project_stats).into())
 }
@@ -353,7 +411,7 @@ pub async fn list_for_user(
                 .await?;
             let proj = state
                 .with_browser(branch, |browser| {
-                    let project_stats = browser.get_stats()?;
+                    let project_stats = Stats::summarise(browser)?;
                     Ok((project, project_stats).into())
                 })
                 .await?;
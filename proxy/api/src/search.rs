@@ -0,0 +1,610 @@
+//! Index of all projects replicated on the device to search across their metadata, file paths and
+//! default branch contents.
+//!
+//! The metadata and file paths of every project are kept in one entry per project, while the
+//! contents are split into the lines of the files and an inverted index from the words to the
+//! lines they occur in, with one posting list per word and project. A query only reads the lines
+//! containing all of its words.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+
+use coco::source::search::Document;
+
+use crate::error;
+
+/// Name for the bucket used in [`kv::Store`].
+const BUCKET_NAME: &str = "search";
+
+/// Name of the bucket mapping words to the [`Posting`]s of the lines they occur in, keyed by
+/// [`term_key`].
+const TERMS_BUCKET_NAME: &str = "search_terms";
+
+/// Name of the bucket holding the indexed lines, keyed by [`line_key`].
+const LINES_BUCKET_NAME: &str = "search_lines";
+
+/// Name of the bucket holding the [`Contents`] of every project.
+const CONTENTS_BUCKET_NAME: &str = "search_contents";
+
+/// Version of the layout of the index. Entries of other versions are rebuilt.
+const INDEX_VERSION: u32 = 2;
+
+/// The default maximum number of [`Match`]es returned by [`search`].
+pub const DEFAULT_LIMIT: usize = 50;
+
+lazy_static::lazy_static! {
+    // Projects are indexed from the peer events and the http handlers concurrently, while
+    // replacing the contents of a project takes several writes which must not interleave.
+    static ref WRITE_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// The indexed information of a single project.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Entry {
+    /// Unique identifier of the project in the network.
+    pub urn: coco::Urn,
+    /// Project name.
+    pub name: String,
+    /// High-level description of the project.
+    pub description: String,
+    /// Handles of the maintainers, or their urns if their identity is not available locally.
+    pub maintainers: Vec<String>,
+    /// Head of the default branch at the time the project was indexed.
+    pub head: Option<coco::oid::Oid>,
+    /// Paths of all files on the default branch.
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Version of the index layout the entry was written with.
+    #[serde(default)]
+    pub version: u32,
+}
+
+/// A line containing a word.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Posting {
+    /// The project the line belongs to.
+    urn: String,
+    /// Absolute path to the file from the root of the repo.
+    path: String,
+    /// The line number in the file, starting from 1.
+    number: usize,
+}
+
+/// What was written to the index for the contents of a project, so it can be taken out again.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Contents {
+    /// The words occurring in the project.
+    terms: Vec<String>,
+    /// The keys of the indexed lines.
+    lines: Vec<String>,
+}
+
+/// A project with at least one match for a query.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hit {
+    /// Unique identifier of the project in the network.
+    pub urn: coco::Urn,
+    /// Project name.
+    pub name: String,
+    /// High-level description of the project.
+    pub description: String,
+    /// What matched the query.
+    pub matches: Vec<Match>,
+}
+
+/// The part of a project matching a query.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum Match {
+    /// The project name matched.
+    Name,
+    /// The project description matched.
+    Description,
+    /// One of the maintainers matched.
+    #[serde(rename_all = "camelCase")]
+    Maintainer {
+        /// Handle of the maintainer.
+        handle: String,
+    },
+    /// The path of a file matched.
+    #[serde(rename_all = "camelCase")]
+    Path {
+        /// Absolute path to the file from the root of the repo.
+        path: String,
+    },
+    /// A line of a file matched.
+    #[serde(rename_all = "camelCase")]
+    Content {
+        /// Absolute path to the file from the root of the repo.
+        path: String,
+        /// The line number in the file, starting from 1.
+        number: usize,
+        /// The content of the line.
+        line: String,
+    },
+}
+
+/// The outcome of a [`search`].
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Results {
+    /// The matching projects, ordered by name.
+    pub hits: Vec<Hit>,
+    /// Set if the search stopped after hitting the limit.
+    pub truncated: bool,
+}
+
+/// Searches the index for projects matching the `query` case-insensitively. Names,
+/// descriptions, maintainers and paths match if they contain the `query`, lines of files if they
+/// contain it and all of its words. At most `limit` [`Match`]es are returned.
+///
+/// # Errors
+///
+/// * if the [`kv::Bucket`] can't be accessed
+/// * if an entry of the index can't be decoded
+pub fn search(store: &kv::Store, query: &str, limit: usize) -> Result<Results, error::Error> {
+    let query = query.to_lowercase();
+    let contains = |text: &str| text.to_lowercase().contains(&query);
+
+    let mut lines = matching_lines(store, &query)?;
+    let mut entries = load(store)?;
+    entries.sort_by(|left, right| left.name.cmp(&right.name));
+
+    let mut results = Results {
+        hits: vec![],
+        truncated: false,
+    };
+    let mut remaining = limit;
+
+    for entry in entries {
+        let mut matches = vec![];
+
+        if contains(&entry.name) {
+            matches.push(Match::Name);
+        }
+        if contains(&entry.description) {
+            matches.push(Match::Description);
+        }
+        for handle in &entry.maintainers {
+            if contains(handle) {
+                matches.push(Match::Maintainer {
+                    handle: handle.clone(),
+                });
+            }
+        }
+        for path in &entry.paths {
+            if contains(path) {
+                matches.push(Match::Path { path: path.clone() });
+            }
+        }
+        if let Some(lines) = lines.remove(&entry.urn.to_string()) {
+            matches.extend(
+                lines
+                    .into_iter()
+                    .map(|((path, number), line)| Match::Content { path, number, line }),
+            );
+        }
+
+        if matches.is_empty() {
+            continue;
+        }
+
+        if matches.len() > remaining {
+            matches.truncate(remaining);
+            results.truncated = true;
+        }
+        remaining -= matches.len();
+
+        if !matches.is_empty() {
+            results.hits.push(Hit {
+                urn: entry.urn,
+                name: entry.name,
+                description: entry.description,
+                matches,
+            });
+        }
+
+        if results.truncated {
+            break;
+        }
+    }
+
+    Ok(results)
+}
+
+/// Looks up the lines containing the lowercased `query`, grouped by project and ordered by path
+/// and line number. Only the lines containing every word of the `query` are read.
+fn matching_lines(
+    store: &kv::Store,
+    query: &str,
+) -> Result<BTreeMap<String, BTreeMap<(String, usize), String>>, error::Error> {
+    let terms_bucket = store.bucket::<&str, kv::Json<Vec<Posting>>>(Some(TERMS_BUCKET_NAME))?;
+    let lines_bucket = store.bucket::<&str, kv::Json<String>>(Some(LINES_BUCKET_NAME))?;
+
+    let mut candidates: Option<HashSet<Posting>> = None;
+    for term in terms(query) {
+        let mut postings = vec![];
+        for item in terms_bucket.iter_prefix(term_key(&term, "").as_str()) {
+            postings.extend(item?.value::<kv::Json<Vec<Posting>>>()?.to_inner());
+        }
+        candidates = Some(match candidates {
+            None => postings.into_iter().collect(),
+            Some(candidates) => postings
+                .into_iter()
+                .filter(|posting| candidates.contains(posting))
+                .collect(),
+        });
+    }
+
+    let mut lines: BTreeMap<String, BTreeMap<(String, usize), String>> = BTreeMap::new();
+    for posting in candidates.unwrap_or_default() {
+        let key = line_key(&posting.urn, &posting.path, posting.number);
+        if let Some(line) = lines_bucket.get(key.as_str())?.map(kv::Json::to_inner) {
+            if line.to_lowercase().contains(query) {
+                lines
+                    .entry(posting.urn)
+                    .or_default()
+                    .insert((posting.path, posting.number), line);
+            }
+        }
+    }
+
+    Ok(lines)
+}
+
+/// (Re-)indexes the project identified by `urn`. Does nothing if the head of its default branch
+/// didn't move since it was last indexed.
+///
+/// # Errors
+///
+/// * if the project or its default branch can't be found
+/// * if the files of the default branch can't be read
+/// * if access to the storage fails
+pub async fn index_project(
+    state: &coco::State,
+    store: &kv::Store,
+    urn: coco::Urn,
+) -> Result<(), error::Error> {
+    let project = state.get_project(urn.clone(), None).await?;
+    let branch = state.find_default_branch(urn.clone()).await?;
+
+    let key = urn.to_string();
+    let previous = store
+        .bucket::<&str, kv::Json<Entry>>(Some(BUCKET_NAME))?
+        .get(key.as_str())?
+        .map(kv::Json::to_inner);
+
+    let head = state
+        .with_browser(branch.clone(), |browser| {
+            Ok(coco::oid::Oid::from(browser.get().first().id))
+        })
+        .await?;
+    if previous.map_or(false, |entry| {
+        entry.head == Some(head) && entry.version == INDEX_VERSION
+    }) {
+        return Ok(());
+    }
+
+    let files = state
+        .with_browser_and_repo(branch, |browser, repo| {
+            coco::source::search::documents(browser, repo, None::<coco::Revision<coco::PeerId>>)
+        })
+        .await?;
+
+    let mut maintainers = vec![];
+    for maintainer in project.maintainers() {
+        let handle = match state.get_user(maintainer.clone()).await {
+            Ok(user) => user.name().to_string(),
+            Err(_) => maintainer.to_string(),
+        };
+        maintainers.push(handle);
+    }
+    maintainers.sort();
+
+    let entry = Entry {
+        urn,
+        name: project.name().to_string(),
+        description: project.description().clone().unwrap_or_default(),
+        maintainers,
+        head: Some(head),
+        paths: files.iter().map(|file| file.path.clone()).collect(),
+        version: INDEX_VERSION,
+    };
+
+    let _guard = WRITE_LOCK.lock().expect("search index lock poisoned");
+    index_contents(store, &entry.urn, &files)?;
+    store
+        .bucket::<&str, kv::Json<Entry>>(Some(BUCKET_NAME))?
+        .set(key.as_str(), kv::Json(entry))?;
+
+    Ok(())
+}
+
+/// Takes the project identified by `urn` out of the index.
+///
+/// # Errors
+///
+/// * if access to the storage fails
+pub fn remove_project(store: &kv::Store, urn: &coco::Urn) -> Result<(), error::Error> {
+    let _guard = WRITE_LOCK.lock().expect("search index lock poisoned");
+    remove_contents(store, urn)?;
+    store
+        .bucket::<&str, kv::Json<Entry>>(Some(BUCKET_NAME))?
+        .remove(urn.to_string().as_str())?;
+
+    Ok(())
+}
+
+/// Replaces the indexed contents of the project identified by `urn` with the lines of its
+/// `files`.
+///
+/// The [`Contents`] are recorded before the lines and postings are written in one batch each, so
+/// a project which was only partially indexed can still be taken out again.
+fn index_contents(
+    store: &kv::Store,
+    urn: &coco::Urn,
+    files: &[Document],
+) -> Result<(), error::Error> {
+    remove_contents(store, urn)?;
+
+    let urn = urn.to_string();
+    let mut lines: Vec<(String, String)> = vec![];
+    let mut postings: BTreeMap<String, Vec<Posting>> = BTreeMap::new();
+
+    for file in files {
+        let content = match &file.content {
+            Some(content) => content,
+            None => continue,
+        };
+        for (idx, line) in content.lines().enumerate() {
+            let line_terms = terms(line);
+            if line_terms.is_empty() {
+                continue;
+            }
+
+            lines.push((line_key(&urn, &file.path, idx + 1), line.to_string()));
+
+            for term in line_terms {
+                postings.entry(term).or_default().push(Posting {
+                    urn: urn.clone(),
+                    path: file.path.clone(),
+                    number: idx + 1,
+                });
+            }
+        }
+    }
+
+    let contents = Contents {
+        terms: postings.keys().cloned().collect(),
+        lines: lines.iter().map(|(key, _line)| key.clone()).collect(),
+    };
+    store
+        .bucket::<&str, kv::Json<Contents>>(Some(CONTENTS_BUCKET_NAME))?
+        .set(urn.as_str(), kv::Json(contents))?;
+
+    let mut batch = kv::Batch::<&str, kv::Json<String>>::new();
+    for (key, line) in lines.iter() {
+        batch.set(key.as_str(), kv::Json(line.clone()))?;
+    }
+    store
+        .bucket::<&str, kv::Json<String>>(Some(LINES_BUCKET_NAME))?
+        .batch(batch)?;
+
+    let keys = postings
+        .keys()
+        .map(|term| term_key(term, &urn))
+        .collect::<Vec<_>>();
+    let mut batch = kv::Batch::<&str, kv::Json<Vec<Posting>>>::new();
+    for (key, (_term, postings)) in keys.iter().zip(postings.into_iter()) {
+        batch.set(key.as_str(), kv::Json(postings))?;
+    }
+    store
+        .bucket::<&str, kv::Json<Vec<Posting>>>(Some(TERMS_BUCKET_NAME))?
+        .batch(batch)?;
+
+    Ok(())
+}
+
+/// Drops the indexed lines of the project identified by `urn` and its postings.
+fn remove_contents(store: &kv::Store, urn: &coco::Urn) -> Result<(), error::Error> {
+    let urn = urn.to_string();
+    let contents_bucket = store.bucket::<&str, kv::Json<Contents>>(Some(CONTENTS_BUCKET_NAME))?;
+    let contents = match contents_bucket.get(urn.as_str())? {
+        Some(contents) => contents.to_inner(),
+        None => return Ok(()),
+    };
+
+    let keys = contents
+        .terms
+        .iter()
+        .map(|term| term_key(term, &urn))
+        .collect::<Vec<_>>();
+    let mut batch = kv::Batch::<&str, kv::Json<Vec<Posting>>>::new();
+    for key in keys.iter() {
+        batch.remove(key.as_str())?;
+    }
+    store
+        .bucket::<&str, kv::Json<Vec<Posting>>>(Some(TERMS_BUCKET_NAME))?
+        .batch(batch)?;
+
+    let mut batch = kv::Batch::<&str, kv::Json<String>>::new();
+    for key in contents.lines.iter() {
+        batch.remove(key.as_str())?;
+    }
+    store
+        .bucket::<&str, kv::Json<String>>(Some(LINES_BUCKET_NAME))?
+        .batch(batch)?;
+
+    contents_bucket.remove(urn.as_str())?;
+
+    Ok(())
+}
+
+/// The distinct lowercased words of `text`, i.e. runs of alphanumeric characters and underscores.
+fn terms(text: &str) -> Vec<String> {
+    let mut terms = text
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    terms.sort();
+    terms.dedup();
+
+    terms
+}
+
+/// The key of the postings of the project `urn` for the `term` in the [`TERMS_BUCKET_NAME`]
+/// bucket. With an empty `urn` it is the prefix of the keys of all projects.
+fn term_key(term: &str, urn: &str) -> String {
+    format!("{}\u{0}{}", term, urn)
+}
+
+/// The key of a line in the [`LINES_BUCKET_NAME`] bucket.
+fn line_key(urn: &str, path: &str, number: usize) -> String {
+    format!("{}\u{0}{}\u{0}{}", urn, path, number)
+}
+
+/// Indexes every project returned by [`coco::State::list_projects`] and drops the entries of
+/// projects which are gone. Failures to index a single project are logged and skipped.
+///
+/// # Errors
+///
+/// * if the projects can't be listed
+/// * if access to the storage fails
+pub async fn index_all(state: &coco::State, store: &kv::Store) -> Result<(), error::Error> {
+    let projects = match state.list_projects().await {
+        // There is no owner yet, so there is nothing to index.
+        Err(coco::state::Error::Storage(coco::git::storage::Error::Config(_err))) => return Ok(()),
+        Err(err) => return Err(err.into()),
+        Ok(projects) => projects,
+    };
+    let urns = projects
+        .iter()
+        .map(|project| project.urn().to_string())
+        .collect::<Vec<_>>();
+
+    for entry in load(store)? {
+        if !urns.contains(&entry.urn.to_string()) {
+            remove_project(store, &entry.urn)?;
+        }
+    }
+
+    for project in projects {
+        if let Err(err) = index_project(state, store, project.urn()).await {
+            log::warn!("Failed to index '{}': {}", project.urn(), err);
+        }
+    }
+
+    Ok(())
+}
+
+/// Re-indexes the projects affected by a [`coco::PeerEvent`], i.e. projects that were fetched
+/// after a gossip message or pushed to locally.
+pub async fn handle_event(state: &coco::State, store: &kv::Store, event: coco::PeerEvent) {
+    let urns = match event {
+        coco::PeerEvent::GossipFetched { gossip, .. } => vec![gossip.urn],
+        coco::PeerEvent::Announced(updates) => updates.into_iter().map(|(urn, _)| urn).collect(),
+        _ => return,
+    };
+
+    for urn in urns {
+        let urn = coco::Urn {
+            path: coco::uri::Path::empty(),
+            ..urn
+        };
+        if let Err(err) = index_project(state, store, urn.clone()).await {
+            log::debug!("Failed to index '{}': {}", urn, err);
+        }
+    }
+}
+
+/// Loads all entries of the index.
+fn load(store: &kv::Store) -> Result<Vec<Entry>, error::Error> {
+    let bucket = store.bucket::<&str, kv::Json<Entry>>(Some(BUCKET_NAME))?;
+    let mut entries = vec![];
+
+    for item in bucket.iter() {
+        entries.push(item?.value::<kv::Json<Entry>>()?.to_inner());
+    }
+
+    Ok(entries)
+}
+
+#[allow(clippy::indexing_slicing)]
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use coco::source::search::Document;
+
+    use super::{index_contents, matching_lines, remove_contents, terms};
+
+    #[test]
+    fn words_are_terms() {
+        assert_eq!(
+            terms("fn main() { let x_1 = Vec::new(); }"),
+            vec!["fn", "let", "main", "new", "vec", "x_1"]
+        );
+    }
+
+    #[test]
+    fn lines_are_found_by_their_words() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let store = kv::Store::new(kv::Config::new(tmp_dir.path().join("store")))?;
+        let urn: coco::Urn = "rad:git:hwd1yrerz7sig1smr8yjs5ue1oij61bfhyx41couxqj61qn5joox5pu4o4c"
+            .parse()
+            .expect("failed to parse URN");
+        let files = vec![
+            Document {
+                path: "README.md".to_string(),
+                content: Some("# Arrows\n\nPointy ARROWS everywhere\n".to_string()),
+            },
+            Document {
+                path: "logo.png".to_string(),
+                content: None,
+            },
+        ];
+
+        index_contents(&store, &urn, &files)?;
+
+        let lines = matching_lines(&store, "pointy arrows")?;
+        let project = &lines[&urn.to_string()];
+        assert_eq!(project.len(), 1);
+        assert_eq!(
+            project[&("README.md".to_string(), 3)],
+            "Pointy ARROWS everywhere"
+        );
+        assert_eq!(matching_lines(&store, "arrows")?[&urn.to_string()].len(), 2);
+        assert!(matching_lines(&store, "arrows pointy")?.is_empty());
+
+        let other: coco::Urn =
+            "rad:git:hwd1yre85ddm5ruz4kgqppdtdgqgqr4wjy3fmskgebhpzwcxshei7d4ouwe"
+                .parse()
+                .expect("failed to parse URN");
+        index_contents(
+            &store,
+            &other,
+            &[Document {
+                path: "arrows.txt".to_string(),
+                content: Some("arrows\n".to_string()),
+            }],
+        )?;
+        assert_eq!(matching_lines(&store, "arrows")?.len(), 2);
+        assert!(matching_lines(&store, "arrow")?.is_empty());
+
+        remove_contents(&store, &urn)?;
+        let lines = matching_lines(&store, "arrows")?;
+        assert_eq!(lines.len(), 1);
+        assert!(lines.contains_key(&other.to_string()));
+
+        Ok(())
+    }
+}
@@ -98,6 +98,7 @@ pub async fn replicate_platinum(
         repo: project::Repo::Existing {
             path: platinum_into.clone(),
         },
+        template: None,
     };
 
     let meta = api.init_project(owner, project_creation).await?;
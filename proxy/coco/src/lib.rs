@@ -40,10 +40,7 @@ pub use radicle_git_ext as git_ext;
 
 pub use radicle_git_helpers::remote_helper;
 
-pub use radicle_surf::{
-    diff::{Diff, FileDiff},
-    vcs::git::Stats,
-};
+pub use radicle_surf::vcs::git::Stats;
 
 pub mod config;
 pub mod control;
@@ -65,9 +62,13 @@ pub mod signer;
 
 pub mod source;
 pub use source::{
-    blob, branches, commit, commit_header, commits, into_branch_type, local_state, revisions, tags,
-    tree, Blob, BlobContent, Branch, Commit, CommitHeader, Info, ObjectType, Person, Revision,
-    Revisions, Tag, Tree, TreeEntry,
+    archive, blame, blob, branches,
+    changeset::{Diff, FileDiff},
+    commit, commit_header, commits, commits_stats, compare, contributors, graph, into_branch_type,
+    languages, local_state, raw, readme, revisions, search, tag, tags, tags_details, tree, Blame,
+    Blob, BlobContent, Branch, BranchRef, Commit, CommitHeader, CommitsFilter, Comparison,
+    Highlight, Info, ObjectType, Person, Revision, Revisions, Tag, TagDetails, TagRef, Tree,
+    TreeEntry,
 };
 
 mod spawn_abortable;
@@ -19,7 +19,7 @@ use crate::{
 };
 
 mod announcement;
-pub use announcement::Announcement;
+pub use announcement::{announce_project, forget as forget_announcements, Announcement};
 
 mod control;
 pub use control::Control;
@@ -55,6 +55,10 @@ pub enum Error {
     /// There was an error when interacting with [`State`].
     #[error(transparent)]
     State(#[from] state::Error),
+
+    /// Failed to access the persistent store.
+    #[error(transparent)]
+    Store(#[from] kv::Error),
 }
 
 /// Local peer to participate in the radicle code-collaboration network.
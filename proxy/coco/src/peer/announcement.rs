@@ -9,6 +9,7 @@ use librad::uri::{path::ParseError, Path, RadUrn};
 use crate::{
     oid::Oid,
     peer::gossip,
+    project::archive,
     state::{self, State},
 };
 
@@ -114,7 +115,12 @@ pub fn load(store: &kv::Store) -> Result<Updates, Error> {
 /// * access to the storage fails
 pub async fn run(state: &State, store: &kv::Store) -> Result<Updates, Error> {
     let old = load(store)?;
-    let new = build(state).await?;
+    let archived = archive::list(store)?;
+    let new = build(state)
+        .await?
+        .into_iter()
+        .filter(|(urn, _)| !archived.iter().any(|project| project.id == urn.id))
+        .collect::<Updates>();
     let updates = diff(&old, &new);
 
     announce(state, updates.iter()).await;
@@ -126,6 +132,43 @@ pub async fn run(state: &State, store: &kv::Store) -> Result<Updates, Error> {
     Ok(updates)
 }
 
+/// Announce the project `urn` right away, unless it is archived. Changes which don't move any
+/// branch, like new metadata, are announced this way.
+///
+/// # Errors
+///
+/// * if the [`kv::Bucket`] can't be accessed
+pub async fn announce_project(
+    state: &State,
+    store: &kv::Store,
+    urn: &RadUrn,
+) -> Result<(), kv::Error> {
+    if !archive::is_archived(store, urn)? {
+        gossip::announce(state, urn, None).await;
+    }
+
+    Ok(())
+}
+
+/// Drop all cached announcements of the project `urn`, so they are announced anew should the
+/// project come back.
+///
+/// # Errors
+///
+/// * if the [`kv::Bucket`] can't be accessed
+/// * if the storage of the remaining updates fails
+pub fn forget(store: &kv::Store, urn: &RadUrn) -> Result<(), kv::Error> {
+    let bucket = store.bucket::<&'static str, kv::Json<Updates>>(Some(BUCKET_NAME))?;
+    let updates = bucket
+        .get(KEY_NAME)?
+        .map_or(HashSet::new(), kv::Json::to_inner)
+        .into_iter()
+        .filter(|(announced, _)| announced.id != urn.id)
+        .collect::<Updates>();
+
+    bucket.set(KEY_NAME, kv::Json(updates))
+}
+
 /// Update the cache with the latest announcements.
 ///
 /// # Errors
@@ -183,9 +183,12 @@ impl Subroutines {
             Command::StartSyncTimeout(sync_period) => {
                 SpawnAbortable::new(start_sync_timeout(sync_period, self.input_sender.clone()))
             },
-            Command::SyncPeer(peer_id) => {
-                SpawnAbortable::new(sync(self.state.clone(), peer_id, self.input_sender.clone()))
-            },
+            Command::SyncPeer(peer_id) => SpawnAbortable::new(sync(
+                self.state.clone(),
+                self.store.clone(),
+                peer_id,
+                self.input_sender.clone(),
+            )),
             Command::EmitEvent(event) => {
                 self.subscriber.send(event).ok();
                 SpawnAbortable::new(async move {})
@@ -307,13 +310,13 @@ async fn persist_waiting_room(waiting_room: WaitingRoom<SystemTime, Duration>, s
 
 /// Run the sync with a single peer to reach state parity for locally tracked projects. On
 /// completion report back with the success or failure.
-async fn sync(state: State, peer_id: PeerId, mut sender: mpsc::Sender<Input>) {
+async fn sync(state: State, store: kv::Store, peer_id: PeerId, mut sender: mpsc::Sender<Input>) {
     sender
         .send(Input::PeerSync(input::Sync::Started(peer_id)))
         .await
         .ok();
 
-    match sync::sync(&state, peer_id).await {
+    match sync::sync(&state, &store, peer_id).await {
         Ok(_) => {
             sender
                 .send(Input::PeerSync(input::Sync::Succeeded(peer_id)))
@@ -2,18 +2,21 @@
 
 use librad::{peer::PeerId, uri::RadUrl};
 
-use crate::state::State;
+use crate::{project::archive, state::State};
 
 use super::{include, Error};
 
-/// Initiaites a fetch for all locally tracked projects from the given [`PeerId`].
-pub async fn sync(state: &State, peer_id: PeerId) -> Result<(), Error> {
+/// Initiaites a fetch for all locally tracked projects from the given [`PeerId`], leaving out
+/// archived ones.
+pub async fn sync(state: &State, store: &kv::Store, peer_id: PeerId) -> Result<(), Error> {
     log::debug!("Starting sync from {}", peer_id);
 
+    let archived = archive::list(store)?;
     let urls = state
         .list_projects()
         .await?
         .iter()
+        .filter(|project| !archived.iter().any(|urn| urn.id == project.urn().id))
         .map(|project| RadUrl {
             authority: peer_id,
             urn: project.urn(),
@@ -5,9 +5,20 @@ use radicle_surf::vcs::git::git2;
 
 use crate::config;
 
+/// Module concerned with shelving projects which are kept around, but no longer announced or
+/// synced.
+pub mod archive;
+
 /// Module concerned with creating new projects and repositories.
 pub mod create;
-pub use create::{Create, Repo};
+pub use create::{Create, Repo, Template};
+
+/// Module concerned with forking projects into new identities.
+pub mod fork;
+
+/// Module concerned with changing the metadata of existing projects.
+pub mod update;
+pub use update::Update;
 
 /// Module concerned with checkout out working copies of projects, as git repositories.
 pub mod checkout;
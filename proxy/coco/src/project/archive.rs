@@ -0,0 +1,101 @@
+use std::collections::HashSet;
+
+use librad::uri::RadUrn;
+
+/// Name for the bucket used in [`kv::Store`].
+const BUCKET_NAME: &str = "archived_projects";
+/// Key for the single value holding the set of archived projects.
+const KEY_NAME: &str = "projects";
+
+/// Mark the project `urn` as archived. Its data is kept, but it is neither announced nor synced
+/// anymore.
+///
+/// # Errors
+///
+/// * if the [`kv::Bucket`] can't be accessed
+pub fn archive(store: &kv::Store, urn: RadUrn) -> Result<(), kv::Error> {
+    let mut archived = list(store)?;
+    archived.insert(strip_path(urn));
+    save(store, archived)
+}
+
+/// Take the project `urn` out of the archive. Returns whether it was archived before.
+///
+/// # Errors
+///
+/// * if the [`kv::Bucket`] can't be accessed
+pub fn unarchive(store: &kv::Store, urn: RadUrn) -> Result<bool, kv::Error> {
+    let mut archived = list(store)?;
+    let removed = archived.remove(&strip_path(urn));
+    if removed {
+        save(store, archived)?;
+    }
+
+    Ok(removed)
+}
+
+/// Check if the project `urn` is archived.
+///
+/// # Errors
+///
+/// * if the [`kv::Bucket`] can't be accessed
+pub fn is_archived(store: &kv::Store, urn: &RadUrn) -> Result<bool, kv::Error> {
+    Ok(list(store)?.iter().any(|archived| archived.id == urn.id))
+}
+
+/// The set of archived projects.
+///
+/// # Errors
+///
+/// * if the [`kv::Bucket`] can't be accessed
+pub fn list(store: &kv::Store) -> Result<HashSet<RadUrn>, kv::Error> {
+    let bucket = store.bucket::<&'static str, kv::Json<HashSet<RadUrn>>>(Some(BUCKET_NAME))?;
+    Ok(bucket
+        .get(KEY_NAME)?
+        .map_or_else(HashSet::new, kv::Json::to_inner))
+}
+
+/// Persist the set of `archived` projects.
+fn save(store: &kv::Store, archived: HashSet<RadUrn>) -> Result<(), kv::Error> {
+    let bucket = store.bucket::<&'static str, kv::Json<HashSet<RadUrn>>>(Some(BUCKET_NAME))?;
+    bucket.set(KEY_NAME, kv::Json(archived))
+}
+
+/// Projects are identified by the root of their urn, regardless of the path pointing into them.
+fn strip_path(urn: RadUrn) -> RadUrn {
+    RadUrn {
+        path: librad::uri::Path::empty(),
+        ..urn
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use librad::{hash::Hash, uri};
+
+    use super::{archive, is_archived, list, unarchive};
+
+    #[test]
+    fn archive_and_unarchive() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let store = kv::Store::new(kv::Config::new(tmp_dir.path().join("store")))?;
+        let urn = uri::RadUrn::new(Hash::hash(b"geez"), uri::Protocol::Git, uri::Path::empty());
+        let branch = uri::RadUrn {
+            path: "rad/master".parse()?,
+            ..urn.clone()
+        };
+
+        assert!(!is_archived(&store, &urn)?);
+
+        archive(&store, branch.clone())?;
+        assert!(is_archived(&store, &urn)?);
+        assert!(is_archived(&store, &branch)?);
+        assert_eq!(list(&store)?.len(), 1);
+
+        assert!(unarchive(&store, urn.clone())?);
+        assert!(!unarchive(&store, urn.clone())?);
+        assert!(!is_archived(&store, &urn)?);
+
+        Ok(())
+    }
+}
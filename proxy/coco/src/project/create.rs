@@ -12,6 +12,8 @@ use radicle_surf::vcs::git::git2;
 
 use crate::user::User;
 
+pub mod template;
+pub use template::Template;
 pub mod validation;
 
 /// Errors that occur when attempting to create a working copy of a project.
@@ -25,6 +27,10 @@ pub enum Error {
     #[error(transparent)]
     Meta(#[from] entity::Error),
 
+    /// The files of the template could not be read.
+    #[error(transparent)]
+    Template(#[from] template::Error),
+
     /// An error occurred while validating input.
     #[error(transparent)]
     Validation(#[from] validation::Error),
@@ -46,6 +52,16 @@ pub enum Repo {
         /// The directory where we create the project.
         path: PathBuf,
     },
+    /// Import a repository by cloning it from `url` into `<path>/<name>`, where the name is the
+    /// last segment of the `url`. The `url` must point to a repository on this machine, e.g.
+    /// `file://` URLs or paths to bare repositories, or to a git bundle file. Remote URLs are
+    /// rejected.
+    Import {
+        /// The location of the repository or bundle to import.
+        url: String,
+        /// The directory where we clone the project into.
+        path: PathBuf,
+    },
 }
 
 impl Repo {
@@ -63,6 +79,9 @@ impl Repo {
                 .map(ToString::to_string)
                 .ok_or_else(|| validation::Error::EmptyExistingPath(path.to_path_buf())),
             Self::New { name, .. } => Ok(name.to_string()),
+            Self::Import { url, .. } => {
+                import_name(url).ok_or_else(|| validation::Error::EmptyImportUrl(url.clone()))
+            },
         }
     }
 
@@ -71,10 +90,30 @@ impl Repo {
         match self {
             Self::Existing { path } => path.to_path_buf(),
             Self::New { name, path } => path.join(name),
+            Self::Import { url, path } => path.join(import_name(url).unwrap_or_default()),
         }
     }
 }
 
+/// The name of a project imported from `url`, i.e. its last segment without any `.git` or
+/// `.bundle` suffix.
+fn import_name(url: &str) -> Option<String> {
+    let segment = url
+        .trim_end_matches(|c| c == '/' || c == '\\')
+        .rsplit(|c| c == '/' || c == '\\' || c == ':')
+        .next()?;
+    let name = segment
+        .strip_suffix(".git")
+        .or_else(|| segment.strip_suffix(".bundle"))
+        .unwrap_or(segment);
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
 /// The data required for creating a new project.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -83,19 +122,41 @@ pub struct Create {
     pub description: String,
     /// The default branch name for the project.
     pub default_branch: OneLevel,
-    /// What kind of working copy we're working with, i.e. new or existing.
+    /// What kind of working copy we're working with, i.e. new, existing or imported.
     pub repo: Repo,
+    /// Where the files of the initial commit of a new repository are taken from. The initial
+    /// commit is empty if there is none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<Template>,
 }
 
 impl Create {
     /// Validate `Create` into a [`validation::Repository`]. This ensures that we have valid
-    /// paths when we attempt to create the working copy.
+    /// paths when we attempt to create the working copy. The `files` are the ones read from the
+    /// [`Template`], if any.
     ///
     /// # Errors
     ///
     /// See [`validation::Repository::validate`]
-    pub fn validate(self, url: LocalUrl) -> Result<validation::Repository, validation::Error> {
-        validation::Repository::validate(self.repo, url, self.default_branch)
+    pub fn validate(
+        self,
+        url: LocalUrl,
+        files: Option<template::Files>,
+    ) -> Result<validation::Repository, validation::Error> {
+        validation::Repository::validate(self.repo, url, self.default_branch, files)
+    }
+
+    /// Ensures a [`Template`] is only given for a **New** repository, before its files are read.
+    ///
+    /// # Errors
+    ///
+    ///   * There is a template for an existing or imported repository.
+    pub fn guard_template(&self) -> Result<(), validation::Error> {
+        if self.template.is_some() && !matches!(self.repo, Repo::New { .. }) {
+            return Err(validation::Error::UnexpectedTemplate);
+        }
+
+        Ok(())
     }
 
     /// Build a [`project::Project`], where the provided [`User`] is the owner, and the set of
@@ -133,6 +194,7 @@ impl Create {
             repo: Repo::Existing { path },
             description: self.description,
             default_branch: self.default_branch,
+            template: None,
         }
     }
 }
@@ -172,9 +234,10 @@ mod test {
                 name: "exists".to_string(),
                 path: tmpdir.path().to_path_buf(),
             },
+            template: None,
         };
         assert_matches!(
-            create.validate(url).err(),
+            create.validate(url, None).err(),
             Some(validation::Error::AlreadExists(_))
         );
 
@@ -199,8 +262,229 @@ mod test {
                 name: "exists".to_string(),
                 path: tmpdir.path().to_path_buf(),
             },
+            template: None,
+        };
+        assert!(create.validate(url, None).is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn import_names() {
+        assert_eq!(
+            import_name("file:///srv/git/radicle-upstream.git"),
+            Some("radicle-upstream".to_string())
+        );
+        assert_eq!(
+            import_name("/srv/git/radicle-upstream/"),
+            Some("radicle-upstream".to_string())
+        );
+        assert_eq!(
+            import_name("backups/radicle-upstream.bundle"),
+            Some("radicle-upstream".to_string())
+        );
+        assert_eq!(import_name("/"), None);
+        assert_eq!(import_name(".git"), None);
+    }
+
+    #[test]
+    fn validation_imports_all_branches() -> Result<(), Box<dyn std::error::Error>> {
+        let peer_id = PeerId::from(SecretKey::new());
+        let url = LocalUrl::from_urn(
+            RadUrn::new(Hash::hash(b"geez"), uri::Protocol::Git, uri::Path::empty()),
+            peer_id,
+        );
+        let tmpdir = tempfile::tempdir().expect("failed to create tmp dir");
+
+        let source_path = tmpdir.path().join("upstream.git");
+        {
+            let source = git2::Repository::init_bare(&source_path)?;
+            let signature = git2::Signature::now("cloudhead", "cloudhead@radicle.xyz")?;
+            let tree = source.find_tree(source.treebuilder(None)?.write()?)?;
+            let oid = source.commit(
+                Some("refs/heads/master"),
+                &signature,
+                &signature,
+                "Initial commit",
+                &tree,
+                &[],
+            )?;
+            source.branch("radicle", &source.find_commit(oid)?, false)?;
+        }
+
+        let create = Create {
+            description: "Radicle".to_string(),
+            default_branch: OneLevel::from(reflike!("radicle")),
+            repo: Repo::Import {
+                url: format!("file://{}", source_path.display()),
+                path: tmpdir.path().join("workspace"),
+            },
+            template: None,
+        };
+        assert_eq!(
+            create.repo.full_path(),
+            tmpdir.path().join("workspace").join("upstream")
+        );
+        assert_matches!(
+            create.validate(url.clone(), None)?,
+            validation::Repository::Existing { repo, .. } => {
+                assert!(repo.find_branch("master", git2::BranchType::Local).is_ok());
+                assert!(repo.find_branch("radicle", git2::BranchType::Local).is_ok());
+            }
+        );
+
+        let missing = Create {
+            description: "Radicle".to_string(),
+            default_branch: OneLevel::from(reflike!("missing")),
+            repo: Repo::Import {
+                url: source_path.display().to_string(),
+                path: tmpdir.path().join("elsewhere"),
+            },
+            template: None,
+        };
+        assert_matches!(
+            missing.validate(url, None).err(),
+            Some(validation::Error::MissingDefaultBranch { .. })
+        );
+        assert!(!tmpdir.path().join("elsewhere").join("upstream").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn validation_imports_bundles() -> Result<(), Box<dyn std::error::Error>> {
+        let peer_id = PeerId::from(SecretKey::new());
+        let url = LocalUrl::from_urn(
+            RadUrn::new(Hash::hash(b"geez"), uri::Protocol::Git, uri::Path::empty()),
+            peer_id,
+        );
+        let tmpdir = tempfile::tempdir().expect("failed to create tmp dir");
+
+        let source_path = tmpdir.path().join("upstream.git");
+        let bundle_path = tmpdir.path().join("upstream.bundle");
+        {
+            let source = git2::Repository::init_bare(&source_path)?;
+            let signature = git2::Signature::now("cloudhead", "cloudhead@radicle.xyz")?;
+            let tree = source.find_tree(source.treebuilder(None)?.write()?)?;
+            let oid = source.commit(
+                Some("refs/heads/master"),
+                &signature,
+                &signature,
+                "Initial commit",
+                &tree,
+                &[],
+            )?;
+            source.branch("radicle", &source.find_commit(oid)?, false)?;
+
+            let output = std::process::Command::new("git")
+                .arg("bundle")
+                .arg("create")
+                .arg(&bundle_path)
+                .arg("--all")
+                .current_dir(&source_path)
+                .output()?;
+            assert!(
+                output.status.success(),
+                "{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        let create = Create {
+            description: "Radicle".to_string(),
+            default_branch: OneLevel::from(reflike!("radicle")),
+            repo: Repo::Import {
+                url: bundle_path.display().to_string(),
+                path: tmpdir.path().join("workspace"),
+            },
+            template: None,
         };
-        assert!(create.validate(url).is_ok());
+        assert_eq!(
+            create.repo.full_path(),
+            tmpdir.path().join("workspace").join("upstream")
+        );
+        assert_matches!(
+            create.validate(url, None)?,
+            validation::Repository::Existing { repo, .. } => {
+                assert!(repo.find_branch("master", git2::BranchType::Local).is_ok());
+                assert!(repo.find_branch("radicle", git2::BranchType::Local).is_ok());
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validation_rejects_remote_imports() {
+        let peer_id = PeerId::from(SecretKey::new());
+        let url = LocalUrl::from_urn(
+            RadUrn::new(Hash::hash(b"geez"), uri::Protocol::Git, uri::Path::empty()),
+            peer_id,
+        );
+        let tmpdir = tempfile::tempdir().expect("failed to create tmp dir");
+
+        for source in &[
+            "https://github.com/radicle-dev/radicle-upstream.git",
+            "ssh://git@github.com/radicle-dev/radicle-upstream.git",
+            "git://github.com/radicle-dev/radicle-upstream.git",
+            "git@github.com:radicle-dev/radicle-upstream.git",
+        ] {
+            let create = Create {
+                description: "Radicle".to_string(),
+                default_branch: OneLevel::from(reflike!("radicle")),
+                repo: Repo::Import {
+                    url: (*source).to_string(),
+                    path: tmpdir.path().join("workspace"),
+                },
+                template: None,
+            };
+            assert_matches!(
+                create.validate(url.clone(), None),
+                Err(validation::Error::RemoteImport(_))
+            );
+        }
+        assert!(!tmpdir.path().join("workspace").exists());
+    }
+
+    #[test]
+    fn validation_removes_failed_imports() -> Result<(), Box<dyn std::error::Error>> {
+        let peer_id = PeerId::from(SecretKey::new());
+        let url = LocalUrl::from_urn(
+            RadUrn::new(Hash::hash(b"geez"), uri::Protocol::Git, uri::Path::empty()),
+            peer_id,
+        );
+        let tmpdir = tempfile::tempdir().expect("failed to create tmp dir");
+
+        let source_path = tmpdir.path().join("upstream.git");
+        {
+            let source = git2::Repository::init_bare(&source_path)?;
+            let signature = git2::Signature::now("cloudhead", "cloudhead@radicle.xyz")?;
+            let tree = source.find_tree(source.treebuilder(None)?.write()?)?;
+            source.commit(
+                Some("refs/heads/master"),
+                &signature,
+                &signature,
+                "Initial commit",
+                &tree,
+                &[],
+            )?;
+        }
+
+        let create = Create {
+            description: "Radicle".to_string(),
+            default_branch: OneLevel::from(reflike!("radicle")),
+            repo: Repo::Import {
+                url: source_path.display().to_string(),
+                path: tmpdir.path().join("workspace"),
+            },
+            template: None,
+        };
+        let full_path = create.repo.full_path();
+        assert_matches!(
+            create.validate(url, None),
+            Err(validation::Error::MissingDefaultBranch { .. })
+        );
+        assert!(!full_path.exists());
 
         Ok(())
     }
@@ -0,0 +1,357 @@
+//! Templates providing the files of the initial commit of a new project.
+
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    convert::TryFrom as _,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use librad::{peer::PeerId, uri::RadUrn};
+use radicle_surf::vcs::git::{self, git2, Browser, Rev};
+
+use crate::source::{self, Revision};
+
+/// Mode git records for executable files.
+const EXECUTABLE_MODE: i32 = 0o100_755;
+
+/// Name of the directory git keeps its data in, or of the file pointing to it elsewhere, which is
+/// never part of a template.
+const GIT_DIR: &str = ".git";
+
+/// Errors that occur when reading the files of a template.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// When trying to read the template, an I/O error occurred.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    /// The path of a directory template does not point to a directory.
+    #[error("the template path '{0}' is not a directory")]
+    NotADirectory(PathBuf),
+}
+
+/// Where the files of the initial commit of a new project are taken from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum Template {
+    /// The files found in a directory on disk, leaving out any `.git` directory.
+    Directory {
+        /// The path to the directory.
+        path: PathBuf,
+    },
+    /// The files of another project at the given revision.
+    #[serde(rename_all = "camelCase")]
+    Project {
+        /// The project to take the files from.
+        urn: RadUrn,
+        /// The revision of the project, the head of its default branch if it is `None`.
+        revision: Option<Revision<PeerId>>,
+    },
+}
+
+/// The values substituted for the placeholders in the files of a template.
+///
+/// Each placeholder is written as `{{<name>}}` in a file, e.g. `{{description}}`. Unknown
+/// placeholders and files which are not valid UTF-8 are copied unchanged.
+#[derive(Debug, Clone, Copy)]
+pub struct Placeholders<'a> {
+    /// Substituted for `{{name}}`.
+    pub name: &'a str,
+    /// Substituted for `{{description}}`.
+    pub description: &'a str,
+    /// Substituted for `{{defaultBranch}}`.
+    pub default_branch: &'a str,
+}
+
+impl<'a> Placeholders<'a> {
+    /// Substitutes the placeholders found in `content` in a single pass, so placeholders within
+    /// the substituted values are kept as they are.
+    #[must_use]
+    pub fn substitute<'b>(&self, content: &'b [u8]) -> Cow<'b, [u8]> {
+        let mut rest = match std::str::from_utf8(content) {
+            Ok(text) if text.contains("{{") => text,
+            _ => return Cow::Borrowed(content),
+        };
+
+        let mut substituted = String::with_capacity(rest.len());
+        while let Some(start) = rest.find("{{") {
+            let (before, placeholder) = rest.split_at(start);
+            substituted.push_str(before);
+            let after = placeholder.strip_prefix("{{").unwrap_or(placeholder);
+
+            let found = after.find("}}").and_then(|end| {
+                let (name, tail) = after.split_at(end);
+                Some((self.value(name)?, tail))
+            });
+            match found {
+                Some((value, tail)) => {
+                    substituted.push_str(value);
+                    rest = tail.strip_prefix("}}").unwrap_or(tail);
+                },
+                None => {
+                    substituted.push_str("{{");
+                    rest = after;
+                },
+            }
+        }
+        substituted.push_str(rest);
+
+        Cow::Owned(substituted.into_bytes())
+    }
+
+    /// The value substituted for the placeholder `name`, if there is one.
+    fn value(&self, name: &str) -> Option<&'a str> {
+        match name {
+            "name" => Some(self.name),
+            "description" => Some(self.description),
+            "defaultBranch" => Some(self.default_branch),
+            _ => None,
+        }
+    }
+}
+
+/// A file of a template.
+#[derive(Debug, Clone, PartialEq)]
+struct File {
+    /// The content of the file.
+    content: Vec<u8>,
+    /// Whether the file is executable.
+    executable: bool,
+}
+
+/// The files of a [`Template`], keyed by their path relative to the root of the repository.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Files(BTreeMap<PathBuf, File>);
+
+impl Files {
+    /// Reads all regular files found below the directory at `path`. Symbolic links and `.git`
+    /// entries are left out, whether they are directories or files like those of worktrees.
+    ///
+    /// # Errors
+    ///
+    ///   * The `path` is not a directory.
+    ///   * Reading the directory or one of its files fails.
+    pub fn from_directory(path: &Path) -> Result<Self, Error> {
+        if !path.is_dir() {
+            return Err(Error::NotADirectory(path.to_path_buf()));
+        }
+
+        let mut files = Self::default();
+        let mut directories = vec![path.to_path_buf()];
+        while let Some(directory) = directories.pop() {
+            for entry in fs::read_dir(directory)? {
+                let entry = entry?;
+                if entry.file_name() == GIT_DIR {
+                    continue;
+                }
+                let file_type = entry.file_type()?;
+                if file_type.is_dir() {
+                    directories.push(entry.path());
+                } else if file_type.is_file() {
+                    let relative = entry
+                        .path()
+                        .strip_prefix(path)
+                        .map(Path::to_path_buf)
+                        .unwrap_or_else(|_| PathBuf::from(entry.file_name()));
+                    files.0.insert(
+                        relative,
+                        File {
+                            content: fs::read(entry.path())?,
+                            executable: is_executable(&entry.metadata()?),
+                        },
+                    );
+                }
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Reads all files in the tree of the `revision`, or the current head of the `browser` if it
+    /// is `None`. Symbolic links and submodules are left out.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`source::Error`] if the revision can't be resolved or its tree can't be read.
+    pub fn from_revision(
+        browser: &mut Browser<'_>,
+        repo: &git2::Repository,
+        revision: Option<Revision<PeerId>>,
+    ) -> Result<Self, source::Error> {
+        if let Some(revision) = revision {
+            browser.rev(Rev::try_from(revision)?)?;
+        }
+
+        let head = browser.get().first().id;
+        let tree = repo
+            .find_commit(head)
+            .and_then(|commit| commit.tree())
+            .map_err(git::error::Error::from)?;
+
+        let mut blobs = vec![];
+        tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+            let mode = entry.filemode();
+            if entry.kind() == Some(git2::ObjectType::Blob) && (mode & 0o170_000) == 0o100_000 {
+                if let Some(name) = entry.name() {
+                    blobs.push((format!("{}{}", root, name), entry.id(), mode));
+                }
+            }
+            git2::TreeWalkResult::Ok
+        })
+        .map_err(git::error::Error::from)?;
+
+        let mut files = Self::default();
+        for (path, oid, mode) in blobs {
+            let blob = repo.find_blob(oid).map_err(git::error::Error::from)?;
+            files.0.insert(
+                PathBuf::from(path),
+                File {
+                    content: blob.content().to_vec(),
+                    executable: mode == EXECUTABLE_MODE,
+                },
+            );
+        }
+
+        Ok(files)
+    }
+
+    /// Writes the files into the working copy at `workdir`, substituting the `placeholders`.
+    /// Returns the paths of the written files relative to `workdir`.
+    ///
+    /// # Errors
+    ///
+    ///   * Creating a directory or writing a file fails.
+    pub fn write(&self, workdir: &Path, placeholders: &Placeholders) -> io::Result<Vec<PathBuf>> {
+        let mut written = Vec::with_capacity(self.0.len());
+
+        for (path, file) in &self.0 {
+            let target = workdir.join(path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&target, placeholders.substitute(&file.content))?;
+            if file.executable {
+                set_executable(&target)?;
+            }
+            written.push(path.clone());
+        }
+
+        Ok(written)
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let mut permissions = fs::metadata(path)?.permissions();
+    permissions.set_mode(permissions.mode() | 0o111);
+    fs::set_permissions(path, permissions)
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{Files, Placeholders};
+
+    #[test]
+    fn copies_directories_with_placeholders() -> Result<(), Box<dyn std::error::Error>> {
+        let template = tempfile::tempdir()?;
+        fs::create_dir_all(template.path().join(".git"))?;
+        fs::write(
+            template.path().join(".git").join("HEAD"),
+            "ref: refs/heads/master",
+        )?;
+        fs::create_dir_all(template.path().join("vendor"))?;
+        fs::write(
+            template.path().join("vendor").join(".git"),
+            "gitdir: ../.git/modules/vendor",
+        )?;
+        fs::create_dir_all(template.path().join("ci"))?;
+        fs::write(
+            template.path().join("ci").join("build.yml"),
+            "branches: [{{defaultBranch}}]",
+        )?;
+        fs::write(
+            template.path().join("README.md"),
+            "# {{name}}\n\n{{description}}\n",
+        )?;
+        fs::write(template.path().join("logo.png"), [0x89, b'{', b'{', 0xff])?;
+
+        let files = Files::from_directory(template.path())?;
+        let workdir = tempfile::tempdir()?;
+        let written = files.write(
+            workdir.path(),
+            &Placeholders {
+                name: "radicle-upstream",
+                description: "Desktop client for radicle",
+                default_branch: "main",
+            },
+        )?;
+
+        assert_eq!(
+            written,
+            vec![
+                "README.md".into(),
+                std::path::PathBuf::from("ci").join("build.yml"),
+                "logo.png".into(),
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(workdir.path().join("README.md"))?,
+            "# radicle-upstream\n\nDesktop client for radicle\n"
+        );
+        assert_eq!(
+            fs::read_to_string(workdir.path().join("ci").join("build.yml"))?,
+            "branches: [main]"
+        );
+        assert_eq!(
+            fs::read(workdir.path().join("logo.png"))?,
+            vec![0x89, b'{', b'{', 0xff]
+        );
+        assert!(!workdir.path().join(".git").exists());
+        assert!(!workdir.path().join("vendor").join(".git").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn substitutes_in_a_single_pass() {
+        let placeholders = Placeholders {
+            name: "{{description}}",
+            description: "{{name}}",
+            default_branch: "main",
+        };
+
+        assert_eq!(
+            placeholders
+                .substitute(b"{{name}}: {{description}} on {{defaultBranch}}, {{unknown}} {{")
+                .as_ref(),
+            &b"{{description}}: {{name}} on main, {{unknown}} {{"[..]
+        );
+    }
+}
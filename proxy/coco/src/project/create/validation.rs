@@ -1,7 +1,13 @@
 //! Validation logic for safely checking that a [`super::Repo`] is valid before setting up the
 //! working copy.
 
-use std::{convert::TryFrom, io, path::PathBuf};
+use std::{
+    convert::TryFrom,
+    fs,
+    io::{self, BufRead as _},
+    path::{Path, PathBuf},
+    process::Command,
+};
 
 use librad::{
     git::{local::url::LocalUrl, types::remote::Remote},
@@ -10,10 +16,12 @@ use librad::{
 };
 use radicle_surf::vcs::git::git2;
 
+use super::template;
 use crate::config;
 
 const USER_NAME: &str = "user.name";
 const USER_EMAIL: &str = "user.email";
+const ORIGIN: &str = "origin";
 
 /// Errors that occur when validating a [`super::Repo`]'s path.
 #[derive(Debug, thiserror::Error)]
@@ -29,10 +37,26 @@ pub enum Error {
     )]
     EmptyExistingPath(PathBuf),
 
+    /// A project is being imported, but we couldn't get the `name` of the project from the last
+    /// segment of the URL.
+    #[error(
+        "the import url provided '{0}' was empty, and we could not get the project name from it"
+    )]
+    EmptyImportUrl(String),
+
     /// An error occurred in `git2` that we could not handle.
     #[error(transparent)]
     Git(#[from] git2::Error),
 
+    /// Cloning the repository that is being imported failed.
+    #[error("the repository at '{url}' could not be imported: {reason}")]
+    Import {
+        /// The location the repository was imported from.
+        url: String,
+        /// Why the clone failed.
+        reason: String,
+    },
+
     /// When trying to inspect a path, an I/O error occurred.
     #[error(transparent)]
     Io(#[from] io::Error),
@@ -74,6 +98,15 @@ pub enum Error {
     #[error("the path provided '{0}' does not exist when it was expected to")]
     PathDoesNotExist(PathBuf),
 
+    /// A project is being imported from a remote location, while only local repositories and
+    /// bundles can be imported.
+    #[error("the import url provided '{0}' is remote, only local repositories and bundles can be imported")]
+    RemoteImport(String),
+
+    /// A template was provided for a repository which already has its own history.
+    #[error("a template can only be used when creating a new repository")]
+    UnexpectedTemplate,
+
     /// The `rad` remote was found, but the URL did not match the URL we were expecting.
     #[error("the `rad` remote was found but the url field does not match the provided url, found: '{found}' expected: '{expected}'")]
     UrlMismatch {
@@ -112,6 +145,8 @@ pub enum Repository {
         url: LocalUrl,
         /// The default branch the repository should be set up with.
         default_branch: OneLevel,
+        /// Whether the repository was cloned while validating an import.
+        imported: bool,
     },
     /// A new repository will be created using these fields.
     New {
@@ -125,6 +160,8 @@ pub enum Repository {
         default_branch: OneLevel,
         /// The signature to be used for creating the first commit.
         signature: Signature,
+        /// The files of the first commit, which is empty if there are none.
+        template: Option<template::Files>,
     },
 }
 
@@ -149,6 +186,17 @@ impl Repository {
     ///   * The path provided does not exist:
     ///         * If it does exist, it should be a directory and it should be empty
     ///
+    /// Only **New** repositories may be set up from the `template` files.
+    ///
+    /// **Import**:
+    ///   * The URL provided points to a local repository or bundle, not a remote one
+    ///   * The URL provided has a last segment, which forms the name of the project
+    ///   * The path `<path>/<name>` is vacant, as for **New**
+    ///   * The repository can be cloned into that path, after which the criteria of **Existing**
+    ///   apply to the clone. Should the clone or the criteria fail, the path is removed again.
+    ///
+    /// Importing blocks while cloning, so it should not be run on an async executor directly.
+    ///
     /// # Errors
     ///
     /// If any of the criteria outlined above are violated, this will result in an [`Error`].
@@ -156,7 +204,12 @@ impl Repository {
         repo: super::Repo,
         url: LocalUrl,
         default_branch: OneLevel,
+        template: Option<template::Files>,
     ) -> Result<Self, Error> {
+        if template.is_some() && !matches!(repo, super::Repo::New { .. }) {
+            return Err(Error::UnexpectedTemplate);
+        }
+
         match repo {
             super::Repo::Existing { path } => {
                 if !path.exists() {
@@ -173,29 +226,10 @@ impl Repository {
                 let repo = git2::Repository::open(path.clone())
                     .or_matches(git_ext::is_not_found_err, || Err(Error::NotARepo(path)))?;
 
-                {
-                    let _default_branch_ref = Self::existing_branch(&repo, &default_branch)?;
-                    let _remote = Self::existing_remote(&repo, &url)?;
-                }
-                Ok(Self::Existing {
-                    repo,
-                    url,
-                    default_branch,
-                })
+                Self::existing(repo, url, default_branch, false)
             },
             super::Repo::New { name, path } => {
-                let repo_path = path.join(name.clone());
-
-                if repo_path.is_file() {
-                    return Err(Error::AlreadExists(repo_path));
-                }
-
-                if repo_path.exists()
-                    && repo_path.is_dir()
-                    && repo_path.read_dir()?.next().is_some()
-                {
-                    return Err(Error::AlreadExists(repo_path));
-                }
+                Self::vacant(path.join(name.clone()))?;
 
                 let signature = Self::existing_author()?;
 
@@ -205,8 +239,41 @@ impl Repository {
                     url,
                     default_branch,
                     signature,
+                    template,
                 })
             },
+            super::Repo::Import { url: source, path } => {
+                if Self::is_remote(&source) {
+                    return Err(Error::RemoteImport(source));
+                }
+                let name = super::import_name(&source)
+                    .ok_or_else(|| Error::EmptyImportUrl(source.clone()))?;
+                let repo_path = path.join(name);
+                Self::vacant(repo_path.clone())?;
+
+                let validated = Self::import(&source, &repo_path)
+                    .and_then(|repo| Self::existing(repo, url, default_branch, true));
+                if validated.is_err() && repo_path.exists() {
+                    log::debug!("Removing failed import @ '{}'", repo_path.display());
+                    fs::remove_dir_all(&repo_path)?;
+                }
+
+                validated
+            },
+        }
+    }
+
+    /// The working copy cloned while validating an import, which is to be removed again should
+    /// setting up the project fail.
+    #[must_use]
+    pub fn imported_path(&self) -> Option<PathBuf> {
+        match self {
+            Self::Existing {
+                repo,
+                imported: true,
+                ..
+            } => repo.workdir().map(Path::to_path_buf),
+            _ => None,
         }
     }
 
@@ -221,6 +288,7 @@ impl Repository {
                 repo,
                 url,
                 default_branch,
+                ..
             } => {
                 log::debug!(
                     "Setting up existing repository @ '{}'",
@@ -235,14 +303,23 @@ impl Repository {
                 url,
                 default_branch,
                 signature,
+                template,
             } => {
-                let path = path.join(name);
+                let placeholders = template::Placeholders {
+                    name: &name,
+                    description,
+                    default_branch: default_branch.as_str(),
+                };
+                let path = path.join(&name);
                 log::debug!("Setting up new repository @ '{}'", path.display());
-                let repo = Self::initialise(path, description, &default_branch)?;
+                let repo = Self::initialise(path.clone(), description, &default_branch)?;
                 Self::initial_commit(
                     &repo,
                     &default_branch,
                     &git2::Signature::try_from(signature)?,
+                    template
+                        .as_ref()
+                        .map(|files| (files, path.as_path(), placeholders)),
                 )?;
                 Self::setup_remote(&repo, url, &default_branch)?;
                 crate::project::set_rad_upstream(&repo, &default_branch)?;
@@ -251,6 +328,129 @@ impl Repository {
         }
     }
 
+    fn existing(
+        repo: git2::Repository,
+        url: LocalUrl,
+        default_branch: OneLevel,
+        imported: bool,
+    ) -> Result<Self, Error> {
+        {
+            let _default_branch_ref = Self::existing_branch(&repo, &default_branch)?;
+            let _remote = Self::existing_remote(&repo, &url)?;
+        }
+        Ok(Self::Existing {
+            repo,
+            url,
+            default_branch,
+            imported,
+        })
+    }
+
+    /// Ensures nothing is in the way of creating a repository at `repo_path`.
+    fn vacant(repo_path: PathBuf) -> Result<(), Error> {
+        if repo_path.is_file() {
+            return Err(Error::AlreadExists(repo_path));
+        }
+
+        if repo_path.exists() && repo_path.is_dir() && repo_path.read_dir()?.next().is_some() {
+            return Err(Error::AlreadExists(repo_path));
+        }
+
+        Ok(())
+    }
+
+    /// Clones the repository or bundle found at `source` into `path`, and creates a local branch
+    /// for every branch of the source so all of them end up in the project.
+    fn import(source: &str, path: &Path) -> Result<git2::Repository, Error> {
+        log::debug!("Importing '{}' @ '{}'", source, path.display());
+        let import_err = |reason: String| Error::Import {
+            url: source.to_string(),
+            reason,
+        };
+
+        let repo = if Self::is_bundle(source)? {
+            // libgit2 can't read bundles, so we leave those to git itself.
+            let output = Command::new("git")
+                .arg("clone")
+                .arg("--origin")
+                .arg(ORIGIN)
+                .arg("--")
+                .arg(source)
+                .arg(path)
+                .output()?;
+            if !output.status.success() {
+                return Err(import_err(
+                    String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                ));
+            }
+            git2::Repository::open(path)?
+        } else {
+            git2::build::RepoBuilder::new()
+                .clone(source, path)
+                .map_err(|err| import_err(err.message().to_string()))?
+        };
+
+        Self::track_remote_branches(&repo)?;
+
+        Ok(repo)
+    }
+
+    /// Whether `source` points to another machine, either by a URL with a scheme other than
+    /// `file` or in the scp-like syntax git uses for ssh, e.g. `host:path`.
+    fn is_remote(source: &str) -> bool {
+        if let Some(idx) = source.find("://") {
+            return source.get(..idx) != Some("file");
+        }
+
+        match (source.find(':'), source.find('/')) {
+            (Some(colon), Some(slash)) => colon < slash && !Path::new(source).exists(),
+            (Some(_colon), None) => !Path::new(source).exists(),
+            (None, _) => false,
+        }
+    }
+
+    /// Whether `source` is a file starting with the signature of a git bundle.
+    fn is_bundle(source: &str) -> Result<bool, Error> {
+        let path = Path::new(source);
+        if !path.is_file() {
+            return Ok(false);
+        }
+
+        let mut signature = String::new();
+        io::BufReader::new(fs::File::open(path)?).read_line(&mut signature)?;
+        Ok(signature.starts_with("# v2 git bundle") || signature.starts_with("# v3 git bundle"))
+    }
+
+    /// Creates a local branch tracking each branch of the `origin` remote that doesn't have one
+    /// yet.
+    fn track_remote_branches(repo: &git2::Repository) -> Result<(), Error> {
+        let prefix = format!("{}/", ORIGIN);
+        for branch in repo.branches(Some(git2::BranchType::Remote))? {
+            let (branch, _) = branch?;
+            let remote_name = match branch.name()? {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+            let local_name = match remote_name.strip_prefix(&prefix) {
+                Some(name) if name != "HEAD" => name,
+                _ => continue,
+            };
+            if repo
+                .find_branch(local_name, git2::BranchType::Local)
+                .is_ok()
+            {
+                continue;
+            }
+
+            log::debug!("Tracking imported branch '{}'", remote_name);
+            let commit = branch.get().peel_to_commit()?;
+            let mut local = repo.branch(local_name, &commit, false)?;
+            local.set_upstream(Some(&remote_name))?;
+        }
+
+        Ok(())
+    }
+
     fn initialise(
         path: PathBuf,
         description: &str,
@@ -270,12 +470,20 @@ impl Repository {
         repo: &git2::Repository,
         default_branch: &OneLevel,
         signature: &git2::Signature<'static>,
-    ) -> Result<(), git2::Error> {
-        // Now let's create an empty tree for this commit
+        template: Option<(&template::Files, &Path, template::Placeholders<'_>)>,
+    ) -> Result<(), Error> {
+        // Now let's create the tree for this commit, which is empty unless there is a template
         let tree_id = {
             let mut index = repo.index()?;
 
-            // For our purposes, we'll leave the index empty for now.
+            if let Some((files, workdir, placeholders)) = template {
+                log::debug!("Copying template files into '{}'", workdir.display());
+                for path in files.write(workdir, &placeholders)? {
+                    index.add_path(&path)?;
+                }
+                index.write()?;
+            }
+
             index.write_tree()?
         };
         {
@@ -0,0 +1,104 @@
+use librad::{
+    keys,
+    meta::{entity, project},
+    peer::PeerId,
+    uri::RadUrn,
+};
+
+use crate::user::User;
+
+use super::create::Error;
+
+/// Label of the relation which points a fork at the project it was forked from.
+pub const UPSTREAM_LABEL: &str = "upstream";
+
+/// Build a [`project::Project`] named `name` which forks the `upstream` project found under
+/// `upstream_urn`. It keeps the description and default branch of the upstream, is owned by the
+/// provided [`User`] and the set of keys starts with the provided [`keys::PublicKey`].
+///
+/// # Errors
+///
+///   * Failed to build the project entity.
+pub fn build(
+    upstream: &project::Project<entity::Draft>,
+    upstream_urn: &RadUrn,
+    name: String,
+    owner: &User,
+    key: keys::PublicKey,
+) -> Result<project::Project<entity::Draft>, Error> {
+    let project = project::Project::<entity::Draft>::create(name, owner.urn())?
+        .to_builder()
+        .set_description(upstream.description().clone().unwrap_or_default())
+        .set_default_branch(upstream.default_branch().to_string())
+        .add_rel(project::Relation::Label(
+            UPSTREAM_LABEL.to_string(),
+            upstream_urn.to_string(),
+        ))
+        .add_key(key)
+        .add_certifier(owner.urn())
+        .build()?;
+
+    Ok(project)
+}
+
+/// The project the given `project` was forked from, if any.
+#[must_use]
+pub fn upstream<ST>(project: &project::Project<ST>) -> Option<RadUrn>
+where
+    ST: Clone,
+{
+    project.rel().iter().find_map(|relation| match relation {
+        project::Relation::Label(label, urn) if label == UPSTREAM_LABEL => urn.parse().ok(),
+        _ => None,
+    })
+}
+
+/// The refspecs which push the branches and tags of the `upstream_urn` project, as seen by
+/// `peer_id` or the local peer if it is `None`, into the branches and tags of the fork.
+#[must_use]
+pub fn refspecs(upstream_urn: &RadUrn, peer_id: Option<PeerId>) -> Vec<String> {
+    let namespace = format!("refs/namespaces/{}/refs", upstream_urn.id);
+    let prefix = match peer_id {
+        Some(peer_id) => format!("{}/remotes/{}", namespace, peer_id),
+        None => namespace,
+    };
+
+    vec![
+        format!("{}/heads/*:refs/heads/*", prefix),
+        format!("{}/tags/*:refs/tags/*", prefix),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use librad::{hash::Hash, keys::SecretKey, peer::PeerId, uri};
+
+    use super::refspecs;
+
+    #[test]
+    fn refspecs_of_peers() {
+        let urn = uri::RadUrn::new(Hash::hash(b"geez"), uri::Protocol::Git, uri::Path::empty());
+        let peer_id = PeerId::from(SecretKey::new());
+
+        assert_eq!(
+            refspecs(&urn, None),
+            vec![
+                format!("refs/namespaces/{}/refs/heads/*:refs/heads/*", urn.id),
+                format!("refs/namespaces/{}/refs/tags/*:refs/tags/*", urn.id),
+            ]
+        );
+        assert_eq!(
+            refspecs(&urn, Some(peer_id)),
+            vec![
+                format!(
+                    "refs/namespaces/{}/refs/remotes/{}/heads/*:refs/heads/*",
+                    urn.id, peer_id
+                ),
+                format!(
+                    "refs/namespaces/{}/refs/remotes/{}/tags/*:refs/tags/*",
+                    urn.id, peer_id
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+use librad::{
+    git_ext::OneLevel,
+    meta::{entity, project},
+    uri::RadUrn,
+};
+
+use crate::user::User;
+
+/// Errors that occur when attempting to update the metadata of a project.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Entity meta error.
+    #[error(transparent)]
+    Meta(#[from] entity::Error),
+
+    /// Only maintainers may change the metadata of a project.
+    #[error("'{0}' is not a maintainer of the project")]
+    NotMaintainer(RadUrn),
+
+    /// The update would have removed every maintainer of the project.
+    #[error("a project needs at least one maintainer")]
+    NoMaintainers,
+}
+
+/// The changes to apply to the metadata of an existing project. Fields which are left out keep
+/// their current value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Update {
+    /// The new description of the project.
+    pub description: Option<String>,
+    /// The new default branch name for the project.
+    pub default_branch: Option<OneLevel>,
+    /// Users to add to the maintainers of the project.
+    #[serde(default)]
+    pub add_maintainers: Vec<RadUrn>,
+    /// Users to remove from the maintainers of the project.
+    #[serde(default)]
+    pub remove_maintainers: Vec<RadUrn>,
+}
+
+impl Update {
+    /// Build the next revision of `project` with the changes applied, on behalf of `editor`.
+    /// Everything the update doesn't touch, like the keys and relations, is carried over.
+    ///
+    /// # Errors
+    ///
+    ///   * The `editor` is not a maintainer of the project.
+    ///   * The update removes all maintainers.
+    ///   * Failed to build the project entity.
+    pub fn build(
+        &self,
+        project: &project::Project<entity::Draft>,
+        editor: &User,
+    ) -> Result<project::Project<entity::Draft>, Error> {
+        if !project.maintainers().contains(&editor.urn()) {
+            return Err(Error::NotMaintainer(editor.urn()));
+        }
+
+        let mut maintainers = project.maintainers().clone();
+        for urn in &self.remove_maintainers {
+            maintainers.remove(urn);
+        }
+        maintainers.extend(self.add_maintainers.iter().cloned());
+        let first = maintainers
+            .iter()
+            .next()
+            .cloned()
+            .ok_or(Error::NoMaintainers)?;
+
+        let description = self
+            .description
+            .clone()
+            .or_else(|| project.description().clone())
+            .unwrap_or_default();
+        let default_branch = self.default_branch.as_ref().map_or_else(
+            || project.default_branch().to_string(),
+            |branch| branch.as_str().to_string(),
+        );
+
+        let mut builder =
+            project::Project::<entity::Draft>::create(project.name().to_string(), first)?
+                .to_builder()
+                .set_parent(project)
+                .set_description(description)
+                .set_default_branch(default_branch);
+        for key in project.keys() {
+            builder = builder.add_key(key.clone());
+        }
+        for relation in project.rel() {
+            builder = builder.add_rel(relation.clone());
+        }
+        for maintainer in maintainers {
+            builder = builder.add_certifier(maintainer);
+        }
+
+        Ok(builder.build()?)
+    }
+}
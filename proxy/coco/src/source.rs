@@ -1,16 +1,18 @@
 //! Source code related functionality.
 
-use std::{convert::TryFrom, fmt, path, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    fmt, path,
+    str::FromStr,
+};
 
 use nonempty::NonEmpty;
 use serde::{ser::SerializeStruct as _, Deserialize, Serialize, Serializer};
-use syntect::{
-    easy::HighlightLines, highlighting::ThemeSet, parsing::SyntaxSet, util::LinesWithEndings,
-};
 
 use librad::peer::PeerId;
 use radicle_surf::{
-    diff, file_system,
+    file_system,
     vcs::git::{self, git2, BranchType, Browser, Rev, Stats},
 };
 
@@ -19,9 +21,39 @@ use crate::{
     project::{peer, Peer},
 };
 
+pub mod archive;
+pub use archive::archive;
+
+pub mod changeset;
+
+pub mod contributors;
+pub use contributors::contributors;
+
+pub mod graph;
+pub use graph::graph;
+
+pub mod highlight;
+
+pub mod languages;
+pub use languages::languages;
+
+pub mod links;
+
+pub mod readme;
+pub use readme::readme;
+
+pub mod search;
+pub use search::search;
+
+pub mod signature;
+
 /// An error occurred when interacting with [`radicle_surf`] for browsing source code.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    /// The commit a page of history should start after is not part of the history.
+    #[error("the cursor '{0}' is not part of the history")]
+    CursorNotFound(Oid),
+
     /// We expect at least one [`crate::source::Revisions`] when looking at a project, however the
     /// computation found none.
     #[error(
@@ -37,6 +69,26 @@ pub enum Error {
     #[error(transparent)]
     Git(#[from] git::error::Error),
 
+    /// Additional syntaxes or themes could not be loaded.
+    #[error(transparent)]
+    Highlight(#[from] syntect::LoadingError),
+
+    /// A page of the commit history was requested without any commits in it.
+    #[error("the limit must be at least 1")]
+    InvalidLimit,
+
+    /// The similarity to detect moved and copied files by is not a percentage.
+    #[error("the similarity {0} is not a percentage between 0 and 100")]
+    InvalidSimilarity(u16),
+
+    /// An I/O error occurred while writing an archive.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// The OpenPGP keyring could not be imported.
+    #[error("failed to import the OpenPGP keyring: {0}")]
+    Keyring(String),
+
     /// When trying to query a repositories branches, but there are none.
     #[error("The repository has no branches")]
     NoBranches,
@@ -44,38 +96,18 @@ pub enum Error {
     /// Trying to find a file path which could not be found.
     #[error("the path '{0}' was not found")]
     PathNotFound(file_system::Path),
-}
 
-lazy_static::lazy_static! {
-    // The syntax set is slow to load (~30ms), so we make sure to only load it once.
-    // It _will_ affect the latency of the first request that uses syntax highlighting,
-    // but this is acceptable for now.
-    static ref SYNTAX_SET: SyntaxSet = {
-        let default_set = SyntaxSet::load_defaults_newlines();
-        let mut builder = default_set.into_builder();
-
-        if cfg!(debug_assertions) {
-            // In development assets are relative to the proxy source.
-            // Don't crash if we aren't able to load additional syntaxes for some reason.
-            builder.add_from_folder("./assets", true).ok();
-        } else {
-            // In production assets are relative to the proxy executable.
-            let exe_path = std::env::current_exe().expect("Can't get current exe path");
-            let root_path = exe_path
-                .parent()
-                .expect("Could not get parent path of current executable");
-            let mut tmp = root_path.to_path_buf();
-            tmp.push("assets");
-            let asset_path = tmp.to_str().expect("Couldn't convert pathbuf to str");
-
-            // Don't crash if we aren't able to load additional syntaxes for some reason.
-            match builder.add_from_folder(asset_path, true) {
-                Ok(_) => (),
-                Err(err) => log::warn!("Syntax builder error : {}", err),
-            };
-        }
-        builder.build()
-    };
+    /// A search query could not be compiled into a regular expression.
+    #[error(transparent)]
+    Regex(#[from] regex::Error),
+
+    /// Trying to find a tag which could not be found.
+    #[error("the tag '{0}' was not found")]
+    TagNotFound(String),
+
+    /// A zip archive could not be written.
+    #[error(transparent)]
+    Zip(#[from] zip::result::ZipError),
 }
 
 /// Branch name representation.
@@ -94,6 +126,46 @@ impl fmt::Display for Branch {
     }
 }
 
+/// A branch of the local peer or of a tracked peer.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchRef {
+    /// Name of the branch.
+    pub name: Branch,
+    /// The peer the branch belongs to, `None` for the branches of the local peer.
+    pub peer_id: Option<PeerId>,
+}
+
+impl From<git::Branch> for BranchRef {
+    fn from(branch: git::Branch) -> Self {
+        let peer_id = match &branch.locality {
+            BranchType::Remote { name: Some(remote) } => remote
+                .split('/')
+                .next()
+                .and_then(|peer_id| PeerId::from_str(peer_id).ok()),
+            _ => None,
+        };
+        // Branches of tracked peers live under `heads/`, next to their tags.
+        let name = branch.name.name();
+        let name = name.strip_prefix("heads/").unwrap_or(name);
+
+        Self {
+            name: Branch(name.to_string()),
+            peer_id,
+        }
+    }
+}
+
+/// A tag of the local peer or of a tracked peer.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRef {
+    /// Name of the tag.
+    pub name: Tag,
+    /// The peer the tag belongs to, `None` for the tags of the local peer.
+    pub peer_id: Option<PeerId>,
+}
+
 /// Tag name representation.
 ///
 /// We still need full tag support.
@@ -121,6 +193,38 @@ impl Serialize for Tag {
     }
 }
 
+/// A [`Tag`] together with the commit it points to and, if the tag is annotated, its annotation.
+pub struct TagDetails {
+    /// The name of the tag.
+    pub name: Tag,
+    /// The commit the tag points to.
+    pub target: CommitHeader,
+    /// Whether the tag is an annotated tag object rather than a lightweight reference.
+    pub annotated: bool,
+    /// The creator of an annotated tag, if recorded.
+    pub tagger: Option<Person>,
+    /// The time the tag was created, or the time of the target commit for lightweight tags.
+    pub time: git2::Time,
+    /// The message of an annotated tag, e.g. release notes.
+    pub message: Option<String>,
+}
+
+impl Serialize for TagDetails {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("TagDetails", 6)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("target", &self.target)?;
+        state.serialize_field("annotated", &self.annotated)?;
+        state.serialize_field("tagger", &self.tagger)?;
+        state.serialize_field("time", &self.time.seconds())?;
+        state.serialize_field("message", &self.message)?;
+        state.end()
+    }
+}
+
 /// Representation of a person (e.g. committer, author, signer) from a repository. Usually
 /// extracted from a signature.
 pub struct Person {
@@ -158,9 +262,11 @@ pub struct Commit {
     /// The change statistics for this commit.
     pub stats: CommitStats,
     /// The changeset introduced by this commit.
-    pub diff: diff::Diff,
-    /// The branch this commit belongs to.
-    pub branch: Branch,
+    pub diff: changeset::Diff,
+    /// The branches of all peers which contain this commit.
+    pub branches: Vec<BranchRef>,
+    /// The tags of all peers which contain this commit.
+    pub tags: Vec<TagRef>,
 }
 
 impl Serialize for Commit {
@@ -168,11 +274,12 @@ impl Serialize for Commit {
     where
         S: Serializer,
     {
-        let mut changeset = serializer.serialize_struct("Commit", 4)?;
+        let mut changeset = serializer.serialize_struct("Commit", 5)?;
         changeset.serialize_field("header", &self.header)?;
         changeset.serialize_field("stats", &self.stats)?;
         changeset.serialize_field("diff", &self.diff)?;
-        changeset.serialize_field("branch", &self.branch)?;
+        changeset.serialize_field("branches", &self.branches)?;
+        changeset.serialize_field("tags", &self.tags)?;
         changeset.end()
     }
 }
@@ -193,6 +300,8 @@ pub struct CommitHeader {
     /// The recorded time of the committer signature. This is a convenience alias until we
     /// expose the actual author and commiter signatures.
     pub committer_time: git2::Time,
+    /// The outcome of verifying the signature of the commit, `None` if it wasn't checked.
+    pub signature: Option<signature::Verification>,
 }
 
 impl CommitHeader {
@@ -221,6 +330,34 @@ impl From<&git::Commit> for CommitHeader {
                 email: commit.committer.email.clone(),
             },
             committer_time: commit.author.time,
+            signature: None,
+        }
+    }
+}
+
+impl From<&git2::Commit<'_>> for CommitHeader {
+    fn from(commit: &git2::Commit<'_>) -> Self {
+        let author = commit.author();
+        let committer = commit.committer();
+        let message = String::from_utf8_lossy(commit.message_bytes()).to_string();
+        let summary = commit.summary_bytes().map_or_else(String::new, |summary| {
+            String::from_utf8_lossy(summary).to_string()
+        });
+
+        Self {
+            sha1: Oid::from(commit.id()),
+            author: Person {
+                name: String::from_utf8_lossy(author.name_bytes()).to_string(),
+                email: String::from_utf8_lossy(author.email_bytes()).to_string(),
+            },
+            summary,
+            message,
+            committer: Person {
+                name: String::from_utf8_lossy(committer.name_bytes()).to_string(),
+                email: String::from_utf8_lossy(committer.email_bytes()).to_string(),
+            },
+            committer_time: author.when(),
+            signature: None,
         }
     }
 }
@@ -230,13 +367,16 @@ impl Serialize for CommitHeader {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("CommitHeader", 6)?;
+        let mut state = serializer.serialize_struct("CommitHeader", 7)?;
         state.serialize_field("sha1", &self.sha1.to_string())?;
         state.serialize_field("author", &self.author)?;
         state.serialize_field("summary", &self.summary)?;
         state.serialize_field("description", &self.description())?;
         state.serialize_field("committer", &self.committer)?;
         state.serialize_field("committerTime", &self.committer_time.seconds())?;
+        if let Some(signature) = &self.signature {
+            state.serialize_field("signature", signature)?;
+        }
         state.end()
     }
 }
@@ -246,19 +386,132 @@ impl Serialize for CommitHeader {
 pub struct Commits {
     /// The commit headers
     pub headers: Vec<CommitHeader>,
-    /// The statistics for the commit headers
-    pub stats: Stats,
+    /// Cursor for the next page, present if there are more commits matching the filter.
+    pub next: Option<Oid>,
+}
+
+/// Line statistics of a single file in a changeset.
+#[derive(Serialize)]
+pub struct FileStats {
+    /// Path of the file from the root of the repo. For deleted files this is the old path.
+    pub path: String,
+    /// Additions.
+    pub additions: u64,
+    /// Deletions.
+    pub deletions: u64,
+}
+
+/// Representation of the changes a revision introduces on top of another one.
+pub struct Comparison {
+    /// The best common ancestor of both revisions, if they share history.
+    pub merge_base: Option<Oid>,
+    /// The commits reachable from the head but not from the base revision.
+    pub commits: Vec<CommitHeader>,
+    /// The change statistics for the whole range.
+    pub stats: CommitStats,
+    /// The change statistics per file.
+    pub files: Vec<FileStats>,
+    /// The combined changeset of the range.
+    pub diff: changeset::Diff,
+}
+
+impl Serialize for Comparison {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Comparison", 5)?;
+        state.serialize_field("mergeBase", &self.merge_base)?;
+        state.serialize_field("commits", &self.commits)?;
+        state.serialize_field("stats", &self.stats)?;
+        state.serialize_field("files", &self.files)?;
+        state.serialize_field("diff", &self.diff)?;
+        state.end()
+    }
+}
+
+/// Narrows down and paginates the history returned by [`commits`].
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitsFilter {
+    /// Only include commits which touched the file or directory under this path.
+    pub path: Option<String>,
+    /// Cursor to start the page after, i.e. the sha1 of the last commit of the previous page.
+    pub after: Option<Oid>,
+    /// Maximum number of commits in the page, which must be at least 1.
+    pub limit: Option<usize>,
+    /// Only include commits authored by this email address.
+    pub author: Option<String>,
+    /// Only include commits committed at or after this time, in seconds since the epoch. Like for
+    /// `git log --since`, this is the time of the committer, not the `committerTime` of the
+    /// [`CommitHeader`], which is the time of the author.
+    pub since: Option<i64>,
+    /// Only include commits committed at or before this time, in seconds since the epoch. See
+    /// `since` for which time that is.
+    pub until: Option<i64>,
+}
+
+impl CommitsFilter {
+    /// Checks the properties of the commit which are cheap to look at, i.e. everything but the
+    /// path.
+    fn matches(&self, commit: &git::Commit) -> bool {
+        let time = commit.committer.time.seconds();
+
+        self.author.as_ref().map_or(true, |email| {
+            email.eq_ignore_ascii_case(&commit.author.email)
+        }) && self.since.map_or(true, |since| time >= since)
+            && self.until.map_or(true, |until| time <= until)
+    }
+}
+
+/// Blame information of a file, grouped into consecutive lines last touched by the same commit.
+#[derive(Serialize)]
+pub struct Blame {
+    /// Absolute path to the blamed file from the root of the repo.
+    pub path: String,
+    /// The hunks of the file in order of appearance.
+    pub hunks: Vec<BlameHunk>,
+}
+
+/// A run of consecutive lines which were last touched by the same commit.
+#[derive(Serialize)]
+pub struct BlameHunk {
+    /// The commit which last touched the lines of this hunk.
+    pub commit: CommitHeader,
+    /// The lines of this hunk.
+    pub lines: Vec<BlameLine>,
+}
+
+/// A single line of a blamed file.
+#[derive(Serialize)]
+pub struct BlameLine {
+    /// The line number in the file, starting from 1.
+    pub number: usize,
+    /// The content of the line, without its line ending.
+    pub content: String,
 }
 
 /// Git object types.
 ///
 /// `shafiul.github.io/gitbook/1_the_git_object_model.html`
-#[derive(Debug, Eq, Ord, PartialOrd, PartialEq)]
+#[derive(Debug, Eq, PartialEq)]
 pub enum ObjectType {
     /// References a list of other trees and blobs.
     Tree,
     /// Used to store file data.
     Blob,
+    /// References a commit of another repository.
+    Submodule(links::Submodule),
+    /// Used to store the target of a symbolic link.
+    Symlink(links::Symlink),
+}
+
+impl ObjectType {
+    /// Whether the object is listed among the directories of a [`Tree`].
+    #[must_use]
+    pub const fn is_directory(&self) -> bool {
+        matches!(self, Self::Tree | Self::Submodule(_))
+    }
 }
 
 impl Serialize for ObjectType {
@@ -269,6 +522,8 @@ impl Serialize for ObjectType {
         match self {
             Self::Blob => serializer.serialize_unit_variant("ObjectType", 0, "BLOB"),
             Self::Tree => serializer.serialize_unit_variant("ObjectType", 1, "TREE"),
+            Self::Submodule(_) => serializer.serialize_unit_variant("ObjectType", 2, "SUBMODULE"),
+            Self::Symlink(_) => serializer.serialize_unit_variant("ObjectType", 3, "SYMLINK"),
         }
     }
 }
@@ -288,10 +543,15 @@ impl Serialize for Info {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Info", 3)?;
+        let mut state = serializer.serialize_struct("Info", 4)?;
         state.serialize_field("name", &self.name)?;
         state.serialize_field("objectType", &self.object_type)?;
         state.serialize_field("lastCommit", &self.last_commit)?;
+        match &self.object_type {
+            ObjectType::Submodule(submodule) => state.serialize_field("submodule", submodule)?,
+            ObjectType::Symlink(symlink) => state.serialize_field("symlink", symlink)?,
+            ObjectType::Tree | ObjectType::Blob => {},
+        }
         state.end()
     }
 }
@@ -342,6 +602,8 @@ pub enum BlobContent {
     Ascii(String),
     /// Content is syntax-highlighted HTML.
     Html(String),
+    /// Content is split into lines of syntax-highlighting tokens.
+    Tokens(Vec<highlight::Line>),
     /// Content is binary and needs special treatment.
     Binary,
 }
@@ -353,6 +615,7 @@ impl Serialize for BlobContent {
     {
         match self {
             Self::Ascii(content) | Self::Html(content) => serializer.serialize_str(content),
+            Self::Tokens(lines) => lines.serialize(serializer),
             Self::Binary => serializer.serialize_none(),
         }
     }
@@ -403,7 +666,7 @@ impl Serialize for TreeEntry {
 }
 
 /// A revision selector for a `Browser`.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum Revision<P> {
     /// Select a tag under the name provided.
@@ -464,16 +727,30 @@ pub struct Revisions<P, U> {
     pub tags: Vec<Tag>,
 }
 
+/// The ways to present the content of a [`Blob`].
+#[derive(Clone, Copy, Debug)]
+pub enum Highlight<'a> {
+    /// The content as is.
+    None,
+    /// Syntax-highlighted HTML, rendered with the theme of the given name.
+    Html(&'a str),
+    /// Lines of syntax-highlighting tokens, limited to the given range.
+    Tokens(highlight::LineRange),
+}
+
 /// Returns the [`Blob`] for a file at `revision` under `path`.
 ///
+/// Submodules are returned without content, symbolic links with their target as content.
+///
 /// # Errors
 ///
 /// Will return [`Error`] if the project doesn't exist or a surf interaction fails.
 pub fn blob<P>(
     browser: &mut Browser,
+    repo: &git2::Repository,
     maybe_revision: Option<Revision<P>>,
     path: &str,
-    theme: Option<&str>,
+    highlight: Highlight<'_>,
 ) -> Result<Blob, Error>
 where
     P: ToString,
@@ -486,9 +763,26 @@ where
     let root = browser.get_directory()?;
     let p = file_system::Path::from_str(path)?;
 
-    let file = root
-        .find_file(p.clone())
-        .ok_or_else(|| Error::PathNotFound(p.clone()))?;
+    let head = browser.get().first().id;
+    let (content, object_type) =
+        match links::find(repo, head, path).map_err(git::error::Error::from)? {
+            Some(object_type @ ObjectType::Submodule(_)) => (BlobContent::Binary, object_type),
+            object_type => {
+                let file = root
+                    .find_file(p.clone())
+                    .ok_or_else(|| Error::PathNotFound(p.clone()))?;
+                let oid = repo
+                    .find_commit(head)
+                    .and_then(|commit| commit.tree())
+                    .and_then(|tree| tree.get_path(path::Path::new(path.trim_matches('/'))))
+                    .map_err(git::error::Error::from)?
+                    .id();
+                (
+                    blob_content(path, oid, &file.contents, highlight),
+                    object_type.unwrap_or(ObjectType::Blob),
+                )
+            },
+        };
 
     let mut commit_path = file_system::Path::root();
     commit_path.append(p.clone());
@@ -498,50 +792,137 @@ where
         .map(|c| CommitHeader::from(&c));
     let (_rest, last) = p.split_last();
 
-    let content = blob_content(path, &file.contents, theme);
-
     Ok(Blob {
         content,
         info: Info {
             name: last.to_string(),
-            object_type: ObjectType::Blob,
+            object_type,
             last_commit,
         },
         path: path.to_string(),
     })
 }
 
-/// Return a [`BlobContent`] given a file path, content and theme. Attempts to perform syntax
-/// highlighting when the theme is `Some`.
-fn blob_content(path: &str, content: &[u8], theme_name: Option<&str>) -> BlobContent {
-    match (std::str::from_utf8(content), theme_name) {
-        (Ok(content), None) => BlobContent::Ascii(content.to_owned()),
-        (Ok(content), Some(theme_name)) => {
-            let syntax = path::Path::new(path)
-                .extension()
-                .and_then(std::ffi::OsStr::to_str)
-                .and_then(|ext| SYNTAX_SET.find_syntax_by_extension(ext));
-
-            let ts = ThemeSet::load_defaults();
-            let theme = ts.themes.get(theme_name);
-
-            match (syntax, theme) {
-                (Some(syntax), Some(theme)) => {
-                    let mut highlighter = HighlightLines::new(syntax, theme);
-                    let mut html = String::with_capacity(content.len());
-
-                    for line in LinesWithEndings::from(content) {
-                        let regions = highlighter.highlight(line, &SYNTAX_SET);
-                        syntect::html::append_highlighted_html_for_styled_line(
-                            &regions[..],
-                            syntect::html::IncludeBackground::No,
-                            &mut html,
-                        );
-                    }
-                    BlobContent::Html(html)
-                },
-                _ => BlobContent::Ascii(content.to_owned()),
-            }
+/// Looks up the blob of the file at `revision` under `path` and passes its raw bytes to
+/// `read`. The bytes are borrowed from the object database, so `read` can copy only the part of
+/// them it needs.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the file doesn't exist or a surf or git interaction fails.
+pub fn raw<P, F, T>(
+    browser: &mut Browser,
+    repo: &git2::Repository,
+    maybe_revision: Option<Revision<P>>,
+    path: &str,
+    read: F,
+) -> Result<T, Error>
+where
+    P: ToString,
+    F: FnOnce(&[u8]) -> T,
+{
+    let maybe_revision = maybe_revision.map(Rev::try_from).transpose()?;
+    if let Some(revision) = maybe_revision {
+        browser.rev(revision)?;
+    }
+
+    let p = file_system::Path::from_str(path)?;
+    let head = browser.get().first().id;
+    let tree = repo
+        .find_commit(head)
+        .and_then(|commit| commit.tree())
+        .map_err(git::error::Error::from)?;
+    let entry = match tree.get_path(path::Path::new(path.trim_matches('/'))) {
+        Ok(entry) if entry.kind() == Some(git2::ObjectType::Blob) => entry,
+        Ok(_) => return Err(Error::PathNotFound(p)),
+        Err(err) if err.code() == git2::ErrorCode::NotFound => return Err(Error::PathNotFound(p)),
+        Err(err) => return Err(git::error::Error::from(err).into()),
+    };
+    let blob = repo
+        .find_blob(entry.id())
+        .map_err(git::error::Error::from)?;
+
+    Ok(read(blob.content()))
+}
+
+/// Returns the [`Blame`] for a file at `revision` under `path`, i.e. the [`CommitHeader`] which
+/// last touched each of its lines.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the file doesn't exist or a surf or git interaction fails.
+pub fn blame<P>(
+    browser: &mut Browser,
+    repo: &git2::Repository,
+    maybe_revision: Option<Revision<P>>,
+    path: &str,
+) -> Result<Blame, Error>
+where
+    P: ToString,
+{
+    let maybe_revision = maybe_revision.map(Rev::try_from).transpose()?;
+    if let Some(revision) = maybe_revision {
+        browser.rev(revision)?;
+    }
+
+    let root = browser.get_directory()?;
+    let p = file_system::Path::from_str(path)?;
+    let file = root
+        .find_file(p.clone())
+        .ok_or_else(|| Error::PathNotFound(p.clone()))?;
+    let content = String::from_utf8_lossy(&file.contents);
+    let lines = content.lines().collect::<Vec<_>>();
+
+    let head = browser.get().first().id;
+    let mut opts = git2::BlameOptions::new();
+    opts.newest_commit(head);
+    let blame = repo
+        .blame_file(path::Path::new(path), Some(&mut opts))
+        .map_err(git::error::Error::from)?;
+
+    let mut hunks = Vec::with_capacity(blame.len());
+    for hunk in blame.iter() {
+        let commit = repo
+            .find_commit(hunk.final_commit_id())
+            .map_err(git::error::Error::from)?;
+        let start = hunk.final_start_line();
+        let lines = (start..start + hunk.lines_in_hunk())
+            .map(|number| BlameLine {
+                number,
+                content: lines
+                    .get(number - 1)
+                    .map_or_else(String::new, |line| (*line).to_string()),
+            })
+            .collect();
+
+        hunks.push(BlameHunk {
+            commit: CommitHeader::from(&commit),
+            lines,
+        });
+    }
+
+    Ok(Blame {
+        path: path.to_string(),
+        hunks,
+    })
+}
+
+/// Return a [`BlobContent`] given a file path, the oid and content of its blob and the way to
+/// [`Highlight`] it. Falls back to the content as is if HTML highlighting is not possible.
+fn blob_content(
+    path: &str,
+    oid: git2::Oid,
+    content: &[u8],
+    highlight: Highlight<'_>,
+) -> BlobContent {
+    match (std::str::from_utf8(content), highlight) {
+        (Ok(content), Highlight::None) => BlobContent::Ascii(content.to_owned()),
+        (Ok(content), Highlight::Html(theme_name)) => {
+            highlight::html(path, oid, content, theme_name)
+                .map_or_else(|| BlobContent::Ascii(content.to_owned()), BlobContent::Html)
+        },
+        (Ok(content), Highlight::Tokens(range)) => {
+            BlobContent::Tokens(highlight::tokens(path, content, range))
         },
         (Err(_), _) => BlobContent::Binary,
     }
@@ -624,89 +1005,408 @@ pub fn commit_header(browser: &mut Browser<'_>, sha1: Oid) -> Result<CommitHeade
     Ok(CommitHeader::from(commit))
 }
 
-/// Retrieves a [`Commit`].
+/// Retrieves a [`Commit`], with its signature verified against the key of `peer_id`, the owner of
+/// the browsed branch. Files sharing at least `similarity` percent of their content are reported
+/// as moved or copied in the diff.
 ///
 /// # Errors
 ///
-/// Will return [`Error`] if the project doesn't exist or the surf interaction fails.
-pub fn commit(browser: &mut Browser<'_>, sha1: Oid) -> Result<Commit, Error> {
+/// Will return [`Error`] if the project doesn't exist, the surf interaction fails or the
+/// `similarity` is above 100.
+pub fn commit(
+    browser: &mut Browser<'_>,
+    repo: &git2::Repository,
+    sha1: Oid,
+    peer_id: &PeerId,
+    similarity: u16,
+) -> Result<Commit, Error> {
+    guard_similarity(similarity)?;
     browser.commit(sha1.into())?;
 
     let history = browser.get();
     let commit = history.first();
 
-    let diff = if let Some(parent) = commit.parents.first() {
-        browser.diff(*parent, sha1.into())?
-    } else {
-        browser.initial_diff(sha1.into())?
-    };
+    let oid: git2::Oid = sha1.into();
+    let diff = changeset::diff(repo, commit.parents.first().copied(), oid, similarity)
+        .map_err(git::error::Error::from)?;
+    let stats = line_stats(&diff);
 
-    let mut deletions = 0;
-    let mut additions = 0;
+    let mut branches = browser
+        .revision_branches(oid)?
+        .into_iter()
+        .map(BranchRef::from)
+        .collect::<Vec<_>>();
+    sort_refs(&mut branches, |branch| (&branch.peer_id, &branch.name));
+    let tags = containing_tags(repo, oid).map_err(git::error::Error::from)?;
 
-    for file in &diff.modified {
-        if let diff::FileDiff::Plain { ref hunks } = file.diff {
-            for hunk in hunks.iter() {
-                for line in &hunk.lines {
-                    match line {
-                        diff::LineDiff::Addition { .. } => additions += 1,
-                        diff::LineDiff::Deletion { .. } => deletions += 1,
-                        _ => {},
-                    }
-                }
+    let mut header = CommitHeader::from(commit);
+    header.signature =
+        Some(signature::verify(repo, oid, peer_id).map_err(git::error::Error::from)?);
+
+    Ok(Commit {
+        header,
+        stats,
+        diff,
+        branches,
+        tags,
+    })
+}
+
+/// Finds the tags of all peers which point to the commit `oid` or one of its descendants, local
+/// tags first.
+fn containing_tags(repo: &git2::Repository, oid: git2::Oid) -> Result<Vec<TagRef>, git2::Error> {
+    let mut tags = vec![];
+
+    for glob in &["refs/tags/*", "refs/remotes/*/tags/*"] {
+        for reference in repo.references_glob(glob)? {
+            let reference = reference?;
+            let name = match reference.name() {
+                Some(name) => strip_namespace(name),
+                None => continue,
+            };
+            let target = match reference.peel_to_commit() {
+                Ok(commit) => commit.id(),
+                // Tags may point to trees or blobs.
+                Err(_err) => continue,
+            };
+            if target != oid && !repo.graph_descendant_of(target, oid)? {
+                continue;
             }
+
+            let tag = match name.strip_prefix("refs/tags/") {
+                Some(name) => TagRef {
+                    name: Tag(name.to_string()),
+                    peer_id: None,
+                },
+                None => {
+                    let mut parts = name.trim_start_matches("refs/remotes/").splitn(3, '/');
+                    let peer_id = parts
+                        .next()
+                        .and_then(|peer_id| PeerId::from_str(peer_id).ok());
+                    let name = parts.nth(1).unwrap_or_default();
+                    TagRef {
+                        name: Tag(name.to_string()),
+                        peer_id,
+                    }
+                },
+            };
+            tags.push(tag);
         }
     }
+    sort_refs(&mut tags, |tag| (&tag.peer_id, &tag.name));
 
-    let oid: git2::Oid = sha1.into();
-    let branches = browser.revision_branches(oid)?;
+    Ok(tags)
+}
 
-    // If a commit figures in more than one branch, there's no real way to know
-    // which branch to show without additional context. So, we choose the first
-    // branch.
-    let branch = branches.first();
+/// Sorts the refs of the local peer first, followed by those of the tracked peers, each by peer
+/// and name. `key` picks the peer and name of a ref.
+fn sort_refs<R, N, F>(refs: &mut [R], key: F)
+where
+    N: Ord,
+    F: Fn(&R) -> (&Option<PeerId>, &N),
+{
+    refs.sort_by(|left, right| {
+        let (left_peer, left_name) = key(left);
+        let (right_peer, right_name) = key(right);
+        left_peer
+            .as_ref()
+            .map(ToString::to_string)
+            .cmp(&right_peer.as_ref().map(ToString::to_string))
+            .then_with(|| left_name.cmp(right_name))
+    });
+}
 
-    // Known commits always have at least one branch. If this isn't the case, it's a bug.
-    let branch = Branch(
-        branch
-            .expect("known commits must be on a branch")
-            .name
-            .to_string(),
-    );
+/// Strips the `refs/namespaces/<namespace>/` prefixes off a reference name.
+fn strip_namespace(mut name: &str) -> &str {
+    while let Some(rest) = name.strip_prefix("refs/namespaces/") {
+        name = rest.splitn(2, '/').nth(1).unwrap_or_default();
+    }
+    name
+}
 
-    Ok(Commit {
-        header: CommitHeader::from(commit),
-        stats: CommitStats {
-            additions,
-            deletions,
-        },
-        branch,
+/// Rejects a `similarity` which is not a percentage.
+fn guard_similarity(similarity: u16) -> Result<(), Error> {
+    if similarity > changeset::MAX_SIMILARITY {
+        return Err(Error::InvalidSimilarity(similarity));
+    }
+
+    Ok(())
+}
+
+/// Counts the lines added and deleted in the changed, moved and copied files of the `diff`.
+fn line_stats(diff: &changeset::Diff) -> CommitStats {
+    let mut deletions = 0;
+    let mut additions = 0;
+
+    let files = diff
+        .modified
+        .iter()
+        .map(|file| &file.diff)
+        .chain(diff.moved.iter().map(|file| &file.diff))
+        .chain(diff.copied.iter().map(|file| &file.diff));
+    for file in files {
+        let (file_additions, file_deletions) = file.line_stats();
+        additions += file_additions;
+        deletions += file_deletions;
+    }
+
+    CommitStats {
+        additions,
+        deletions,
+    }
+}
+
+/// Compares the `head` revision against the `base` revision, i.e. provides what `head` adds on
+/// top of `base`.
+///
+/// The diff is computed from the merge base of the two revisions, if there is one, so changes
+/// which only happened on `base` in the meantime are not part of it. Files sharing at least
+/// `similarity` percent of their content are reported as moved or copied.
+///
+/// # Errors
+///
+/// Will return [`Error`] if either revision can't be resolved, a surf or git interaction fails or
+/// the `similarity` is above 100.
+pub fn compare<P>(
+    browser: &mut Browser<'_>,
+    repo: &git2::Repository,
+    base: Revision<P>,
+    head: Revision<P>,
+    similarity: u16,
+) -> Result<Comparison, Error>
+where
+    P: ToString,
+{
+    guard_similarity(similarity)?;
+    let base = resolve(browser, base)?;
+    let head = resolve(browser, head)?;
+
+    let merge_base = match repo.merge_base(base, head) {
+        Ok(oid) => Some(oid),
+        Err(err) if err.code() == git2::ErrorCode::NotFound => None,
+        Err(err) => return Err(git::error::Error::from(err).into()),
+    };
+
+    let commits = range(repo, base, head).map_err(git::error::Error::from)?;
+    let from = merge_base.unwrap_or(base);
+    let diff =
+        changeset::diff(repo, Some(from), head, similarity).map_err(git::error::Error::from)?;
+    let files = file_stats(repo, from, head, similarity).map_err(git::error::Error::from)?;
+    let stats = CommitStats {
+        additions: files.iter().map(|file| file.additions).sum(),
+        deletions: files.iter().map(|file| file.deletions).sum(),
+    };
+
+    Ok(Comparison {
+        merge_base: merge_base.map(Oid::from),
+        commits,
+        stats,
+        files,
         diff,
     })
 }
 
-/// Retrieves the [`Commit`] history for the given `revision`.
+/// Returns the oid of the commit the `revision` points to, or of the current head of the
+/// `browser` if none is given.
 ///
 /// # Errors
 ///
-/// Will return [`Error`] if the project doesn't exist or the surf interaction fails.
+/// Will return [`Error`] if the revision can't be resolved.
+pub fn head<P>(browser: &mut Browser<'_>, maybe_revision: Option<Revision<P>>) -> Result<Oid, Error>
+where
+    P: ToString,
+{
+    match maybe_revision {
+        Some(revision) => resolve(browser, revision).map(Oid::from),
+        None => Ok(browser.get().first().id.into()),
+    }
+}
+
+/// Sets the `browser` to the given `revision` and returns the oid of the commit it points to.
+fn resolve<P>(browser: &mut Browser<'_>, revision: Revision<P>) -> Result<git2::Oid, Error>
+where
+    P: ToString,
+{
+    browser.rev(Rev::try_from(revision)?)?;
+    Ok(browser.get().first().id)
+}
+
+/// Lists the commits reachable from `head` but not from `base`, newest first.
+fn range(
+    repo: &git2::Repository,
+    base: git2::Oid,
+    head: git2::Oid,
+) -> Result<Vec<CommitHeader>, git2::Error> {
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    walk.push(head)?;
+    walk.hide(base)?;
+
+    walk.map(|oid| {
+        let commit = repo.find_commit(oid?)?;
+        Ok(CommitHeader::from(&commit))
+    })
+    .collect()
+}
+
+/// Computes the additions and deletions per file between the trees of the commits `from` and
+/// `to`.
+fn file_stats(
+    repo: &git2::Repository,
+    from: git2::Oid,
+    to: git2::Oid,
+    similarity: u16,
+) -> Result<Vec<FileStats>, git2::Error> {
+    let diff = changeset::git_diff(repo, Some(from), to, similarity)?;
+
+    let mut files = Vec::with_capacity(diff.deltas().len());
+    for (idx, delta) in diff.deltas().enumerate() {
+        let path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let (additions, deletions) = match git2::Patch::from_diff(&diff, idx)? {
+            Some(patch) => {
+                let (_context, additions, deletions) = patch.line_stats()?;
+                (additions as u64, deletions as u64)
+            },
+            // Binary files don't carry line information.
+            None => (0, 0),
+        };
+
+        files.push(FileStats {
+            path,
+            additions,
+            deletions,
+        });
+    }
+
+    Ok(files)
+}
+
+/// Retrieves the [`Commit`] history for the given `revision`, narrowed down and paginated by the
+/// given [`CommitsFilter`]. Signatures are not verified for the history, the `signature` of the
+/// headers is always `None`, see [`commit`] instead.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the project doesn't exist, the surf interaction fails, the `limit` of
+/// the `filter` is 0 or its `after` cursor is not part of the history.
 pub fn commits<P>(
     browser: &mut Browser<'_>,
+    repo: &git2::Repository,
     maybe_revision: Option<Revision<P>>,
+    filter: &CommitsFilter,
 ) -> Result<Commits, Error>
 where
     P: ToString,
 {
+    if filter.limit == Some(0) {
+        return Err(Error::InvalidLimit);
+    }
+
     let maybe_revision = maybe_revision.map(Rev::try_from).transpose()?;
 
     if let Some(revision) = maybe_revision {
         browser.rev(revision)?;
     }
 
-    let headers = browser.get().iter().map(CommitHeader::from).collect();
-    let stats = browser.get_stats()?;
+    let path = filter.path.as_deref().map(path::Path::new);
+    let limit = filter.limit.unwrap_or(usize::MAX);
+    let mut after = filter.after.map(git2::Oid::from);
+    let mut headers: Vec<CommitHeader> = vec![];
+    let mut next = None;
+
+    for commit in browser.get().iter() {
+        // Skip everything up to and including the cursor.
+        if let Some(cursor) = after {
+            if commit.id == cursor {
+                after = None;
+            }
+            continue;
+        }
+
+        if !filter.matches(commit) {
+            continue;
+        }
+
+        if let Some(path) = path {
+            if !touches_path(repo, commit.id, path).map_err(git::error::Error::from)? {
+                continue;
+            }
+        }
+
+        // There is at least one more matching commit, so point the cursor at the end of this
+        // page.
+        if headers.len() >= limit {
+            next = headers.last().map(|last| last.sha1);
+            break;
+        }
+
+        headers.push(CommitHeader::from(commit));
+    }
+
+    // An unknown cursor would otherwise look like the end of the history.
+    if let Some(cursor) = after {
+        return Err(Error::CursorNotFound(cursor.into()));
+    }
 
-    Ok(Commits { headers, stats })
+    Ok(Commits { headers, next })
+}
+
+/// Retrieves the [`Stats`] for the given `revision`.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the project doesn't exist or the surf interaction fails.
+pub fn commits_stats<P>(
+    browser: &mut Browser<'_>,
+    maybe_revision: Option<Revision<P>>,
+) -> Result<Stats, Error>
+where
+    P: ToString,
+{
+    let maybe_revision = maybe_revision.map(Rev::try_from).transpose()?;
+
+    if let Some(revision) = maybe_revision {
+        browser.rev(revision)?;
+    }
+
+    Ok(browser.get_stats()?)
+}
+
+/// Determines if the commit with the given `oid` changed the file or directory under `path`
+/// compared to its parents. Merge commits only count if the entry differs from all of their
+/// parents, which is what `git log -- <path>` does by default.
+fn touches_path(
+    repo: &git2::Repository,
+    oid: git2::Oid,
+    path: &path::Path,
+) -> Result<bool, git2::Error> {
+    let entry_id = |commit: &git2::Commit<'_>| -> Result<Option<git2::Oid>, git2::Error> {
+        match commit.tree()?.get_path(path) {
+            Ok(entry) => Ok(Some(entry.id())),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    };
+
+    let commit = repo.find_commit(oid)?;
+    let current = entry_id(&commit)?;
+
+    if commit.parent_count() == 0 {
+        return Ok(current.is_some());
+    }
+
+    for parent in commit.parents() {
+        if entry_id(&parent)? == current {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
 }
 
 /// Retrieves the list of [`Tag`] for the given project `id`.
@@ -726,13 +1426,119 @@ pub fn tags(browser: &Browser<'_>) -> Result<Vec<Tag>, Error> {
     Ok(tags)
 }
 
+/// Retrieves the [`TagDetails`] of all tags of the project, most recent first. Tags which don't
+/// point to a commit are left out.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the surf or git interactions fail.
+pub fn tags_details(
+    browser: &Browser<'_>,
+    repo: &git2::Repository,
+) -> Result<Vec<TagDetails>, Error> {
+    let mut tags = vec![];
+    for tag in self::tags(browser)? {
+        if let Some(details) = tag_details(repo, tag).map_err(git::error::Error::from)? {
+            tags.push(details);
+        }
+    }
+
+    tags.sort_by(|left, right| {
+        right
+            .time
+            .seconds()
+            .cmp(&left.time.seconds())
+            .then_with(|| left.name.cmp(&right.name))
+    });
+
+    Ok(tags)
+}
+
+/// Retrieves the [`TagDetails`] of the tag `name`.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the tag doesn't exist or doesn't point to a commit, or a git
+/// interaction fails.
+pub fn tag(repo: &git2::Repository, name: &str) -> Result<TagDetails, Error> {
+    tag_details(repo, Tag(name.to_string()))
+        .map_err(git::error::Error::from)?
+        .ok_or_else(|| Error::TagNotFound(name.to_string()))
+}
+
+/// Looks up the reference of the tag `name` and the commit it points to, following chains of
+/// annotated tags.
+///
+/// Returns `None` if there is no such tag or it doesn't point to a commit.
+fn tag_details(repo: &git2::Repository, name: Tag) -> Result<Option<TagDetails>, git2::Error> {
+    let reference = match repo.find_reference(&format!("refs/tags/{}", name)) {
+        Ok(reference) => reference,
+        Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let object = match reference.resolve()?.target() {
+        Some(oid) => repo.find_object(oid, None)?,
+        None => return Ok(None),
+    };
+
+    let annotation = object.as_tag().map(|tag| {
+        let tagger = tag.tagger();
+        (
+            tagger.as_ref().map(|tagger| Person {
+                name: String::from_utf8_lossy(tagger.name_bytes()).to_string(),
+                email: String::from_utf8_lossy(tagger.email_bytes()).to_string(),
+            }),
+            tagger.map(|tagger| tagger.when()),
+            tag.message_bytes()
+                .map(|message| String::from_utf8_lossy(message).to_string()),
+        )
+    });
+
+    let mut target = object;
+    loop {
+        let next = match target.as_tag() {
+            Some(tag) => tag.target()?,
+            None => break,
+        };
+        target = next;
+    }
+    let commit = match target.into_commit() {
+        Ok(commit) => commit,
+        Err(_object) => return Ok(None),
+    };
+    let target = CommitHeader::from(&commit);
+
+    Ok(Some(match annotation {
+        Some((tagger, time, message)) => TagDetails {
+            name,
+            time: time.unwrap_or(target.committer_time),
+            target,
+            annotated: true,
+            tagger,
+            message,
+        },
+        None => TagDetails {
+            name,
+            time: target.committer_time,
+            target,
+            annotated: false,
+            tagger: None,
+            message: None,
+        },
+    }))
+}
+
 /// Retrieve the [`Tree`] for the given `revision` and directory `prefix`.
 ///
+/// Every entry carries the last commit which touched it, all of them found with a single walk of
+/// the history.
+///
 /// # Errors
 ///
 /// Will return [`Error`] if any of the surf interactions fail.
 pub fn tree<P>(
     browser: &mut Browser<'_>,
+    repo: &git2::Repository,
     maybe_revision: Option<Revision<P>>,
     maybe_prefix: Option<String>,
 ) -> Result<Tree, Error>
@@ -746,7 +1552,9 @@ where
         browser.rev(revision)?;
     }
 
-    let path = if prefix == "/" || prefix.is_empty() {
+    let git_prefix = Some(path::Path::new(prefix.trim_matches('/')))
+        .filter(|git_prefix| !git_prefix.as_os_str().is_empty());
+    let path = if git_prefix.is_none() {
         file_system::Path::root()
     } else {
         file_system::Path::from_str(&prefix)?
@@ -760,29 +1568,55 @@ where
             .find_directory(path.clone())
             .ok_or_else(|| Error::PathNotFound(path.clone()))?
     };
-    let mut prefix_contents = prefix_dir.list_directory();
-    prefix_contents.sort();
+    let head = browser.get().first().id;
+    let mut links = links::entries(repo, head, git_prefix).map_err(git::error::Error::from)?;
 
-    let entries_results: Result<Vec<TreeEntry>, Error> = prefix_contents
-        .iter()
+    let mut prefix_contents = prefix_dir
+        .list_directory()
+        .into_iter()
         .map(|(label, system_type)| {
+            let name = label.to_string();
+            let object_type = links.remove(&name).unwrap_or(match system_type {
+                file_system::SystemType::Directory => ObjectType::Tree,
+                file_system::SystemType::File => ObjectType::Blob,
+            });
+            (name, object_type)
+        })
+        .collect::<Vec<_>>();
+    // Submodules surf doesn't list at all.
+    prefix_contents.extend(links.into_iter());
+    prefix_contents.sort_by(|(left, _), (right, _)| left.cmp(right));
+
+    let names = prefix_contents
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+    let last_commits =
+        last_commits(repo, head, git_prefix, &names).map_err(git::error::Error::from)?;
+    let commit_header = |oid: &git2::Oid| -> Result<CommitHeader, Error> {
+        let commit = repo.find_commit(*oid).map_err(git::error::Error::from)?;
+        Ok(CommitHeader::from(&commit))
+    };
+
+    let entries_results: Result<Vec<TreeEntry>, Error> = prefix_contents
+        .into_iter()
+        .map(|(name, object_type)| {
             let entry_path = if path.is_root() {
-                file_system::Path::new(label.clone())
+                file_system::Path::from_str(&name)?
             } else {
                 let mut p = path.clone();
-                p.push(label.clone());
+                p.append(file_system::Path::from_str(&name)?);
                 p
             };
-            let mut commit_path = file_system::Path::root();
-            commit_path.append(entry_path.clone());
 
             let info = Info {
-                name: label.to_string(),
-                object_type: match system_type {
-                    file_system::SystemType::Directory => ObjectType::Tree,
-                    file_system::SystemType::File => ObjectType::Blob,
-                },
-                last_commit: None,
+                last_commit: last_commits
+                    .entries
+                    .get(&name)
+                    .map(commit_header)
+                    .transpose()?,
+                name,
+                object_type,
             };
 
             Ok(TreeEntry {
@@ -794,16 +1628,14 @@ where
 
     let mut entries = entries_results?;
 
-    // We want to ensure that in the response Tree entries come first. `Ord` being derived on
-    // the enum ensures Variant declaration order.
-    //
-    // https://doc.rust-lang.org/std/cmp/trait.Ord.html#derivable
-    entries.sort_by(|a, b| a.info.object_type.cmp(&b.info.object_type));
+    // We want to ensure that in the response directories, i.e. trees and submodules, come first.
+    // The sort is stable, so entries stay ordered by name otherwise.
+    entries.sort_by_key(|entry| !entry.info.object_type.is_directory());
 
     let last_commit = if path.is_root() {
         Some(CommitHeader::from(browser.get().first()))
     } else {
-        None
+        last_commits.tree.as_ref().map(commit_header).transpose()?
     };
     let name = if path.is_root() {
         "".into()
@@ -824,6 +1656,101 @@ where
     })
 }
 
+/// The last commits which touched a directory and its entries.
+struct LastCommits {
+    /// The last commit which touched anything under the directory.
+    tree: Option<git2::Oid>,
+    /// The last commit per entry name.
+    entries: HashMap<String, git2::Oid>,
+}
+
+/// Finds the [`LastCommits`] for the entries `names` of the directory under `prefix`, or the root
+/// directory if it is `None`, in a single walk of the history starting at `head`.
+///
+/// A commit touched an entry if the entry differs from all of the commit's parents, the same
+/// rule [`touches_path`] applies. Commits which didn't change the directory at all are skipped
+/// by comparing the directory's tree oids, before looking at any of the entries.
+fn last_commits(
+    repo: &git2::Repository,
+    head: git2::Oid,
+    prefix: Option<&path::Path>,
+    names: &[String],
+) -> Result<LastCommits, git2::Error> {
+    let mut pending = names.iter().map(String::as_str).collect::<HashSet<_>>();
+    let mut last = LastCommits {
+        tree: None,
+        entries: HashMap::with_capacity(names.len()),
+    };
+
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    walk.push(head)?;
+
+    for oid in walk {
+        if pending.is_empty() && last.tree.is_some() {
+            break;
+        }
+
+        let commit = repo.find_commit(oid?)?;
+        let tree = subtree(repo, &commit, prefix)?;
+        let parents = commit
+            .parents()
+            .map(|parent| subtree(repo, &parent, prefix))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let tree_id = tree.as_ref().map(git2::Tree::id);
+        if parents
+            .iter()
+            .any(|parent| parent.as_ref().map(git2::Tree::id) == tree_id)
+        {
+            continue;
+        }
+
+        if last.tree.is_none() {
+            last.tree = Some(commit.id());
+        }
+
+        let entry_id = |tree: &Option<git2::Tree<'_>>, name: &str| {
+            tree.as_ref()
+                .and_then(|tree| tree.get_name(name))
+                .map(|entry| entry.id())
+        };
+        let entries = &mut last.entries;
+        pending.retain(|name| {
+            let current = entry_id(&tree, name);
+            if parents
+                .iter()
+                .all(|parent| entry_id(parent, name) != current)
+            {
+                entries.insert((*name).to_string(), commit.id());
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    Ok(last)
+}
+
+/// Looks up the tree of the directory under `prefix` in the given `commit`, or its root tree if
+/// `prefix` is `None`.
+fn subtree<'repo>(
+    repo: &'repo git2::Repository,
+    commit: &git2::Commit<'repo>,
+    prefix: Option<&path::Path>,
+) -> Result<Option<git2::Tree<'repo>>, git2::Error> {
+    let tree = commit.tree()?;
+    match prefix {
+        None => Ok(Some(tree)),
+        Some(prefix) => match tree.get_path(prefix) {
+            Ok(entry) => Ok(entry.to_object(repo)?.into_tree().ok()),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(err) => Err(err),
+        },
+    }
+}
+
 /// Provide the [`Revisions`] for the given `peer_id`, looking for the branches as
 /// [`BranchType::Remote`].
 ///
@@ -961,4 +1888,13 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn strip_namespace() {
+        assert_eq!(
+            super::strip_namespace("refs/namespaces/hnrk/refs/remotes/hyn/tags/v1"),
+            "refs/remotes/hyn/tags/v1"
+        );
+        assert_eq!(super::strip_namespace("refs/tags/v1"), "refs/tags/v1");
+    }
 }
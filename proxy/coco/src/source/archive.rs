@@ -0,0 +1,354 @@
+//! Snapshots of a project revision packed into a single downloadable file.
+
+use std::{
+    convert::TryFrom as _,
+    io::{self, Write},
+    path::PathBuf,
+    thread,
+};
+
+use futures::{channel::mpsc, executor, SinkExt as _};
+use serde::{Deserialize, Serialize};
+
+use radicle_surf::vcs::git::{self, git2, Browser, Rev};
+
+use super::{Error, Revision};
+
+/// Mode git records for symbolic links.
+const SYMLINK_MODE: i32 = 0o120_000;
+
+/// Number of hex characters of the commit id used in archive names.
+const SHORT_ID_LENGTH: usize = 7;
+
+/// Stands in for a name which has no characters left once it is made safe.
+const FALLBACK_NAME: &str = "archive";
+
+/// Number of bytes handed out at once when streaming an archive.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Number of chunks held back while the receiving end of a stream is busy.
+const CHUNK_BUFFER: usize = 4;
+
+/// The kinds of archives that can be built.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Format {
+    /// A gzip compressed tarball.
+    #[serde(rename = "tar.gz")]
+    TarGz,
+    /// A zip file.
+    #[serde(rename = "zip")]
+    Zip,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self::TarGz
+    }
+}
+
+impl Format {
+    /// The file extension of archives in this format.
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::TarGz => "tar.gz",
+            Self::Zip => "zip",
+        }
+    }
+
+    /// The MIME type of archives in this format.
+    #[must_use]
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::TarGz => "application/gzip",
+            Self::Zip => "application/zip",
+        }
+    }
+}
+
+/// A snapshot of a revision, ready to be packed.
+#[derive(Debug)]
+pub struct Archive {
+    /// Suggested file name, e.g. `git-platinum-a0b1c2d.tar.gz`. All files in the archive are
+    /// located in a directory named alike, without the extension.
+    pub file_name: String,
+    /// The format of the archive.
+    pub format: Format,
+    /// The name of the directory holding all files, i.e. the file name without the extension.
+    stem: String,
+    /// Modification time of the files, the commit time of the revision.
+    time: u64,
+    /// The files to be packed.
+    entries: Vec<Entry>,
+}
+
+impl Archive {
+    /// Packs the archive into `writer`, reading the files from `repo`.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error`] if a blob can't be read or the archive can't be written.
+    pub fn write<W>(&self, repo: &git2::Repository, mut writer: W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        match self.format {
+            Format::TarGz => tar_gz(repo, &self.stem, self.time, &self.entries, &mut writer)?,
+            Format::Zip => zip(repo, &self.stem, &self.entries, &mut writer)?,
+        }
+
+        Ok(writer.flush()?)
+    }
+
+    /// Packs the archive on a separate thread, handing out the packed bytes in chunks while they
+    /// are written. Zip files are packed in memory and only handed out once they are complete,
+    /// see [`zip`]. The files are read from the monorepo at `path`, see
+    /// [`crate::state::State::monorepo`].
+    ///
+    /// If packing fails, the error is the last item of the stream. Packing stops once the
+    /// receiver is dropped.
+    #[must_use]
+    pub fn stream(self, path: PathBuf) -> mpsc::Receiver<Result<Vec<u8>, io::Error>> {
+        let (mut sender, receiver) = mpsc::channel(CHUNK_BUFFER);
+
+        thread::spawn(move || {
+            let chunks = Chunks {
+                sender: sender.clone(),
+                buffer: Vec::with_capacity(CHUNK_SIZE),
+            };
+            let result = git2::Repository::open(path)
+                .map_err(|err| Error::from(git::error::Error::from(err)))
+                .and_then(|repo| self.write(&repo, chunks));
+
+            if let Err(err) = result {
+                let err = io::Error::new(io::ErrorKind::Other, err.to_string());
+                // Nobody is left to tell if the receiver is gone.
+                executor::block_on(sender.send(Err(err))).ok();
+            }
+        });
+
+        receiver
+    }
+}
+
+/// A file to be added to an [`Archive`].
+#[derive(Debug)]
+struct Entry {
+    /// Path relative to the root of the repo.
+    path: String,
+    /// The file mode as recorded by git.
+    mode: i32,
+    /// The content of the blob.
+    oid: git2::Oid,
+}
+
+/// Lists the tree of the `revision` as an [`Archive`] of the given `format`, to be packed with
+/// [`Archive::write`] or [`Archive::stream`]. The `name`, usually the project name, and the
+/// abbreviated commit id make up the name of the archive. As the name may come from another peer,
+/// it is reduced to `[A-Za-z0-9._-]` without leading dots, so it can't escape the directory the
+/// archive is extracted to.
+///
+/// Submodules are left out. Symbolic links are preserved in tarballs, while zip files contain a
+/// regular file holding the link target.
+///
+/// # Errors
+///
+/// Will return [`Error`] if a surf or git interaction fails.
+pub fn archive<P>(
+    browser: &mut Browser<'_>,
+    repo: &git2::Repository,
+    maybe_revision: Option<Revision<P>>,
+    name: &str,
+    format: Format,
+) -> Result<Archive, Error>
+where
+    P: ToString,
+{
+    let maybe_revision = maybe_revision.map(Rev::try_from).transpose()?;
+    if let Some(revision) = maybe_revision {
+        browser.rev(revision)?;
+    }
+
+    let head = browser.get().first().id;
+    let commit = repo.find_commit(head).map_err(git::error::Error::from)?;
+    let entries = entries(&commit).map_err(git::error::Error::from)?;
+
+    let id = head.to_string();
+    let stem = format!("{}-{}", safe_name(name), &id[..SHORT_ID_LENGTH]);
+    let time = u64::try_from(commit.time().seconds()).unwrap_or_default();
+
+    Ok(Archive {
+        file_name: format!("{}.{}", stem, format.extension()),
+        format,
+        stem,
+        time,
+        entries,
+    })
+}
+
+/// Replaces every character of `name` outside of `[A-Za-z0-9._-]` with `_` and strips leading
+/// dots.
+fn safe_name(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    let name = name.trim_start_matches('.');
+
+    if name.is_empty() {
+        FALLBACK_NAME.to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Lists all blobs and symbolic links in the tree of the `commit`.
+fn entries(commit: &git2::Commit<'_>) -> Result<Vec<Entry>, git2::Error> {
+    let tree = commit.tree()?;
+    let mut entries = vec![];
+
+    tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+        if entry.kind() == Some(git2::ObjectType::Blob) {
+            if let Some(name) = entry.name() {
+                entries.push(Entry {
+                    path: format!("{}{}", root, name),
+                    mode: entry.filemode(),
+                    oid: entry.id(),
+                });
+            }
+        }
+        git2::TreeWalkResult::Ok
+    })?;
+
+    Ok(entries)
+}
+
+/// Writes the `entries` into a gzip compressed tarball.
+fn tar_gz<W>(
+    repo: &git2::Repository,
+    stem: &str,
+    time: u64,
+    entries: &[Entry],
+    out: W,
+) -> Result<(), Error>
+where
+    W: Write,
+{
+    let encoder = flate2::write::GzEncoder::new(out, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+
+    for entry in entries {
+        let blob = repo.find_blob(entry.oid).map_err(git::error::Error::from)?;
+        let path = format!("{}/{}", stem, entry.path);
+
+        let mut header = tar::Header::new_gnu();
+        header.set_mtime(time);
+        if entry.mode == SYMLINK_MODE {
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_mode(0o777);
+            header.set_size(0);
+            let target = String::from_utf8_lossy(blob.content()).into_owned();
+            builder.append_link(&mut header, path, target)?;
+        } else {
+            header.set_entry_type(tar::EntryType::Regular);
+            #[allow(clippy::cast_sign_loss)]
+            header.set_mode((entry.mode & 0o777) as u32);
+            header.set_size(blob.content().len() as u64);
+            builder.append_data(&mut header, path, blob.content())?;
+        }
+    }
+
+    builder.into_inner()?.finish()?;
+
+    Ok(())
+}
+
+/// Writes the `entries` into a zip file.
+///
+/// The headers of a zip file are completed by seeking back once an entry is written, so the whole
+/// file is buffered in memory and copied to `out` when finished.
+fn zip<W>(repo: &git2::Repository, stem: &str, entries: &[Entry], mut out: W) -> Result<(), Error>
+where
+    W: Write,
+{
+    let mut buffer = io::Cursor::new(vec![]);
+    let mut writer = zip::ZipWriter::new(&mut buffer);
+
+    for entry in entries {
+        let blob = repo.find_blob(entry.oid).map_err(git::error::Error::from)?;
+        #[allow(clippy::cast_sign_loss)]
+        let options = zip::write::FileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .unix_permissions((entry.mode & 0o777) as u32);
+
+        writer.start_file(format!("{}/{}", stem, entry.path), options)?;
+        writer.write_all(blob.content())?;
+    }
+
+    writer.finish()?;
+    drop(writer);
+
+    out.write_all(buffer.get_ref())?;
+
+    Ok(())
+}
+
+/// Hands out everything written to it in chunks of [`CHUNK_SIZE`] bytes.
+struct Chunks {
+    /// Where the chunks are sent to.
+    sender: mpsc::Sender<Result<Vec<u8>, io::Error>>,
+    /// The bytes of the chunk being filled.
+    buffer: Vec<u8>,
+}
+
+impl Chunks {
+    /// Sends the buffered bytes, blocking while the channel is full.
+    fn send(&mut self) -> io::Result<()> {
+        let chunk = std::mem::replace(&mut self.buffer, Vec::with_capacity(CHUNK_SIZE));
+
+        executor::block_on(self.sender.send(Ok(chunk)))
+            .map_err(|err| io::Error::new(io::ErrorKind::BrokenPipe, err))
+    }
+}
+
+impl Write for Chunks {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        let (bytes, _rest) = bytes.split_at(bytes.len().min(CHUNK_SIZE - self.buffer.len()));
+        self.buffer.extend_from_slice(bytes);
+        if self.buffer.len() == CHUNK_SIZE {
+            self.send()?;
+        }
+
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            Ok(())
+        } else {
+            self.send()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::safe_name;
+
+    #[test]
+    fn names_stay_inside_the_archive() {
+        assert_eq!(safe_name("git-platinum"), "git-platinum");
+        assert_eq!(safe_name("../../etc/passwd"), "_.._etc_passwd");
+        assert_eq!(safe_name("..\\windows"), "_windows");
+        assert_eq!(safe_name(".hidden v1.0"), "hidden_v1.0");
+        assert_eq!(safe_name("..."), "archive");
+    }
+}
@@ -0,0 +1,333 @@
+//! Changesets between two commits, with renamed and copied files detected.
+
+use serde::Serialize;
+
+use radicle_surf::vcs::git::git2;
+
+/// Percentage of content two files need to share to be considered a rename or copy, the same
+/// default as `git diff -M -C`.
+pub const DEFAULT_SIMILARITY: u16 = 50;
+
+/// Highest possible similarity, requiring files to be identical.
+pub const MAX_SIMILARITY: u16 = 100;
+
+/// The changes between two commits.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diff {
+    /// Paths of the files which were added.
+    pub created: Vec<String>,
+    /// Paths of the files which were removed.
+    pub deleted: Vec<String>,
+    /// Files which were moved to a new path, possibly with changes.
+    pub moved: Vec<MovedFile>,
+    /// Files which were copied to a new path, possibly with changes.
+    pub copied: Vec<CopiedFile>,
+    /// Files which were changed in place.
+    pub modified: Vec<ModifiedFile>,
+}
+
+/// A file which was moved.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MovedFile {
+    /// Path of the file before the move.
+    pub old_path: String,
+    /// Path of the file after the move.
+    pub new_path: String,
+    /// The changes to the content of the file.
+    pub diff: FileDiff,
+}
+
+/// A file which was copied.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopiedFile {
+    /// Path of the file the copy was made from.
+    pub old_path: String,
+    /// Path of the copy.
+    pub new_path: String,
+    /// The changes of the copy compared to the original.
+    pub diff: FileDiff,
+}
+
+/// A file which was changed in place.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModifiedFile {
+    /// Path of the file.
+    pub path: String,
+    /// The changes to the content of the file.
+    pub diff: FileDiff,
+}
+
+/// The changes to the content of a file.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum FileDiff {
+    /// The file is binary, so there are no lines to compare.
+    Binary,
+    /// The changed lines of a text file.
+    Plain {
+        /// The changed regions of the file.
+        hunks: Vec<Hunk>,
+    },
+}
+
+/// A changed region of a file.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hunk {
+    /// The hunk header, e.g. `@@ -1,4 +1,5 @@`.
+    pub header: String,
+    /// The lines of the region.
+    pub lines: Vec<LineDiff>,
+}
+
+/// A single line of a [`Hunk`].
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum LineDiff {
+    /// The line was added.
+    #[serde(rename_all = "camelCase")]
+    Addition {
+        /// Content of the line.
+        line: String,
+        /// Number of the line in the new file.
+        line_num: u32,
+    },
+    /// The line was removed.
+    #[serde(rename_all = "camelCase")]
+    Deletion {
+        /// Content of the line.
+        line: String,
+        /// Number of the line in the old file.
+        line_num: u32,
+    },
+    /// The line is unchanged and shown for context.
+    #[serde(rename_all = "camelCase")]
+    Context {
+        /// Content of the line.
+        line: String,
+        /// Number of the line in the old file.
+        line_num_old: u32,
+        /// Number of the line in the new file.
+        line_num_new: u32,
+    },
+}
+
+impl FileDiff {
+    /// Counts the lines added and deleted.
+    #[must_use]
+    pub fn line_stats(&self) -> (u64, u64) {
+        let mut additions = 0;
+        let mut deletions = 0;
+
+        if let Self::Plain { hunks } = self {
+            for line in hunks.iter().flat_map(|hunk| hunk.lines.iter()) {
+                match line {
+                    LineDiff::Addition { .. } => additions += 1,
+                    LineDiff::Deletion { .. } => deletions += 1,
+                    LineDiff::Context { .. } => {},
+                }
+            }
+        }
+
+        (additions, deletions)
+    }
+}
+
+/// Computes the [`Diff`] from the commit `from` to the commit `to`, or of all files in `to` if
+/// there is no `from`. Files sharing at least `similarity` percent of their content with a removed
+/// or changed file are reported as moved or copied.
+///
+/// # Errors
+///
+/// Will return [`git2::Error`] if the commits can't be read.
+pub fn diff(
+    repo: &git2::Repository,
+    from: Option<git2::Oid>,
+    to: git2::Oid,
+    similarity: u16,
+) -> Result<Diff, git2::Error> {
+    let git_diff = git_diff(repo, from, to, similarity)?;
+    let mut diff = Diff::default();
+
+    for (idx, delta) in git_diff.deltas().enumerate() {
+        let old_path = path(delta.old_file());
+        let new_path = path(delta.new_file());
+
+        match delta.status() {
+            git2::Delta::Added => diff.created.push(new_path),
+            git2::Delta::Deleted => diff.deleted.push(old_path),
+            git2::Delta::Renamed => diff.moved.push(MovedFile {
+                old_path,
+                new_path,
+                diff: file_diff(&git_diff, idx)?,
+            }),
+            git2::Delta::Copied => diff.copied.push(CopiedFile {
+                old_path,
+                new_path,
+                diff: file_diff(&git_diff, idx)?,
+            }),
+            git2::Delta::Modified | git2::Delta::Typechange => diff.modified.push(ModifiedFile {
+                path: new_path,
+                diff: file_diff(&git_diff, idx)?,
+            }),
+            _ => {},
+        }
+    }
+
+    Ok(diff)
+}
+
+/// Diffs the trees of the commits `from` and `to` with rename and copy detection applied.
+pub(super) fn git_diff(
+    repo: &git2::Repository,
+    from: Option<git2::Oid>,
+    to: git2::Oid,
+    similarity: u16,
+) -> Result<git2::Diff<'_>, git2::Error> {
+    let old_tree = match from {
+        Some(from) => Some(repo.find_commit(from)?.tree()?),
+        None => None,
+    };
+    let new_tree = repo.find_commit(to)?.tree()?;
+    let mut diff = repo.diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), None)?;
+
+    let mut find = git2::DiffFindOptions::new();
+    find.renames(true)
+        .copies(true)
+        .rename_threshold(similarity)
+        .copy_threshold(similarity);
+    diff.find_similar(Some(&mut find))?;
+
+    Ok(diff)
+}
+
+/// Collects the hunks of the delta at `idx`.
+fn file_diff(diff: &git2::Diff<'_>, idx: usize) -> Result<FileDiff, git2::Error> {
+    let patch = match git2::Patch::from_diff(diff, idx)? {
+        Some(patch) if !patch.delta().flags().is_binary() => patch,
+        _ => return Ok(FileDiff::Binary),
+    };
+
+    let mut hunks = Vec::with_capacity(patch.num_hunks());
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, len) = patch.hunk(hunk_idx)?;
+        let mut lines = Vec::with_capacity(len);
+
+        for line_idx in 0..len {
+            let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+            let content = String::from_utf8_lossy(line.content()).to_string();
+            let line = match (line.origin(), line.old_lineno(), line.new_lineno()) {
+                ('+', _, Some(line_num)) => LineDiff::Addition {
+                    line: content,
+                    line_num,
+                },
+                ('-', Some(line_num), _) => LineDiff::Deletion {
+                    line: content,
+                    line_num,
+                },
+                (' ', Some(line_num_old), Some(line_num_new)) => LineDiff::Context {
+                    line: content,
+                    line_num_old,
+                    line_num_new,
+                },
+                // Markers for missing newlines at the end of the file.
+                _ => continue,
+            };
+            lines.push(line);
+        }
+
+        hunks.push(Hunk {
+            header: String::from_utf8_lossy(hunk.header()).to_string(),
+            lines,
+        });
+    }
+
+    Ok(FileDiff::Plain { hunks })
+}
+
+/// The path of a side of a delta, relative to the root of the repo.
+fn path(file: git2::DiffFile<'_>) -> String {
+    file.path()
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use radicle_surf::vcs::git::git2;
+
+    use super::{diff, FileDiff, DEFAULT_SIMILARITY};
+
+    /// Commits a tree holding the given files on top of `parent`.
+    fn commit(
+        repo: &git2::Repository,
+        parent: Option<git2::Oid>,
+        files: &[(&str, &str)],
+    ) -> Result<git2::Oid, git2::Error> {
+        let mut builder = repo.treebuilder(None)?;
+        for (name, content) in files {
+            builder.insert(name, repo.blob(content.as_bytes())?, 0o100_644)?;
+        }
+        let tree = repo.find_tree(builder.write()?)?;
+        let signature = git2::Signature::now("Jane Doe", "jane@example.com")?;
+        let parents = match parent {
+            Some(parent) => vec![repo.find_commit(parent)?],
+            None => vec![],
+        };
+        let parents = parents.iter().collect::<Vec<_>>();
+
+        repo.commit(None, &signature, &signature, "test", &tree, &parents)
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    fn moved_files_are_detected() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let repo = git2::Repository::init(tmp_dir.path())?;
+        let content = (1..=20)
+            .map(|n| format!("line {}\n", n))
+            .collect::<String>();
+        let changed = content.replace("line 20", "line twenty");
+
+        let first = commit(&repo, None, &[("old.txt", &content)])?;
+        let second = commit(&repo, Some(first), &[("new.txt", &changed)])?;
+
+        let moved = diff(&repo, Some(first), second, DEFAULT_SIMILARITY)?;
+        assert!(moved.created.is_empty());
+        assert!(moved.deleted.is_empty());
+        assert_eq!(moved.moved.len(), 1);
+        assert_eq!(moved.moved[0].old_path, "old.txt");
+        assert_eq!(moved.moved[0].new_path, "new.txt");
+        assert_eq!(moved.moved[0].diff.line_stats(), (1, 1));
+
+        // Without detection the move falls apart into a deletion and an addition.
+        let split = diff(&repo, Some(first), second, 100)?;
+        assert_eq!(split.created, vec!["new.txt".to_string()]);
+        assert_eq!(split.deleted, vec!["old.txt".to_string()]);
+        assert!(split.moved.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    fn binary_files_have_no_hunks() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let repo = git2::Repository::init(tmp_dir.path())?;
+
+        let first = commit(&repo, None, &[("image.bin", "a\0b")])?;
+        let second = commit(&repo, Some(first), &[("image.bin", "c\0d")])?;
+
+        let changes = diff(&repo, Some(first), second, DEFAULT_SIMILARITY)?;
+        assert_eq!(changes.modified.len(), 1);
+        assert!(matches!(changes.modified[0].diff, FileDiff::Binary));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+//! Activity of the authors of a project over time.
+
+use std::{collections::HashMap, convert::TryFrom as _};
+
+use serde::{Deserialize, Serialize};
+
+use radicle_surf::vcs::git::{self, git2, Browser, Rev};
+
+use super::{changeset, line_stats, Error, Revision};
+
+/// Seconds in a week.
+const WEEK: i64 = 7 * 24 * 60 * 60;
+
+/// Offset of the first Monday after the unix epoch, which was a Thursday.
+const FIRST_MONDAY: i64 = 4 * 24 * 60 * 60;
+
+/// The activity of a single author.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Contributor {
+    /// Name of the author, as found on their most recent commit.
+    pub name: String,
+    /// Email of the author, which tells authors apart.
+    pub email: String,
+    /// Number of commits authored.
+    pub commits: u64,
+    /// Number of lines added.
+    pub additions: u64,
+    /// Number of lines deleted.
+    pub deletions: u64,
+    /// The activity per week, oldest first. Weeks without commits are left out.
+    pub weeks: Vec<Week>,
+}
+
+/// The activity of an author within a week.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Week {
+    /// Start of the week, Monday 00:00 UTC, in seconds since the unix epoch.
+    pub start: i64,
+    /// Number of commits authored.
+    pub commits: u64,
+    /// Number of lines added.
+    pub additions: u64,
+    /// Number of lines deleted.
+    pub deletions: u64,
+}
+
+/// Walks the history of the `revision` and sums up the commits, additions and deletions of every
+/// author, bucketed by the week the commits were authored in. Contributors are ordered by their
+/// number of commits, most active first.
+///
+/// Lines are counted the same way as for a single [`super::Commit`], so moved and copied files
+/// only count with their changed lines. Merge commits are left out, as their changes were already
+/// counted for the commits being merged.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the revision can't be resolved or a surf interaction fails.
+pub fn contributors<P>(
+    browser: &mut Browser<'_>,
+    repo: &git2::Repository,
+    maybe_revision: Option<Revision<P>>,
+) -> Result<Vec<Contributor>, Error>
+where
+    P: ToString,
+{
+    let maybe_revision = maybe_revision.map(Rev::try_from).transpose()?;
+    if let Some(revision) = maybe_revision {
+        browser.rev(revision)?;
+    }
+
+    let history = browser.get();
+    let mut contributors: HashMap<String, Contributor> = HashMap::new();
+    let mut weeks: HashMap<(String, i64), Week> = HashMap::new();
+
+    // The history is ordered newest first, so the first name seen for an email is the most
+    // recent one.
+    for commit in history.iter() {
+        if commit.parents.len() > 1 {
+            continue;
+        }
+
+        let diff = changeset::diff(
+            repo,
+            commit.parents.first().copied(),
+            commit.id,
+            changeset::DEFAULT_SIMILARITY,
+        )
+        .map_err(git::error::Error::from)?;
+        let stats = line_stats(&diff);
+        let email = commit.author.email.to_lowercase();
+        let time = commit.author.time.seconds();
+        let start = time - (time - FIRST_MONDAY).rem_euclid(WEEK);
+
+        let contributor = contributors
+            .entry(email.clone())
+            .or_insert_with(|| Contributor {
+                name: commit.author.name.clone(),
+                email: commit.author.email.clone(),
+                commits: 0,
+                additions: 0,
+                deletions: 0,
+                weeks: vec![],
+            });
+        contributor.commits += 1;
+        contributor.additions += stats.additions;
+        contributor.deletions += stats.deletions;
+
+        let week = weeks.entry((email, start)).or_insert(Week {
+            start,
+            commits: 0,
+            additions: 0,
+            deletions: 0,
+        });
+        week.commits += 1;
+        week.additions += stats.additions;
+        week.deletions += stats.deletions;
+    }
+
+    for ((email, _start), week) in weeks {
+        if let Some(contributor) = contributors.get_mut(&email) {
+            contributor.weeks.push(week);
+        }
+    }
+
+    let mut contributors = contributors
+        .into_iter()
+        .map(|(_email, mut contributor)| {
+            contributor.weeks.sort_by_key(|week| week.start);
+            contributor
+        })
+        .collect::<Vec<_>>();
+    contributors.sort_by(|left, right| {
+        right
+            .commits
+            .cmp(&left.commits)
+            .then_with(|| left.email.cmp(&right.email))
+    });
+
+    Ok(contributors)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use radicle_surf::vcs::git::{self, git2};
+
+    use super::{contributors, Revision, FIRST_MONDAY, WEEK};
+
+    /// Commits a tree holding the given files on top of `parents` to the branch `name`.
+    fn commit(
+        repo: &git2::Repository,
+        name: &str,
+        parents: &[git2::Oid],
+        files: &[(&str, &str)],
+    ) -> Result<git2::Oid, git2::Error> {
+        let mut builder = repo.treebuilder(None)?;
+        for (name, content) in files {
+            builder.insert(name, repo.blob(content.as_bytes())?, 0o100_644)?;
+        }
+        let tree = repo.find_tree(builder.write()?)?;
+        let signature = git2::Signature::now("Jane Doe", "jane@example.com")?;
+        let parents = parents
+            .iter()
+            .map(|parent| repo.find_commit(*parent))
+            .collect::<Result<Vec<_>, _>>()?;
+        let parents = parents.iter().collect::<Vec<_>>();
+
+        repo.commit(
+            Some(&format!("refs/heads/{}", name)),
+            &signature,
+            &signature,
+            "test",
+            &tree,
+            &parents,
+        )
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    fn merges_are_left_out() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let repo = git2::Repository::init(tmp_dir.path())?;
+        let first = commit(&repo, "master", &[], &[("a.txt", "1\n2\n3\n")])?;
+        let second = commit(&repo, "master", &[first], &[("a.txt", "1\n2\nthree\n")])?;
+        let side = commit(&repo, "side", &[first], &[("a.txt", "1\n2\n3\n")])?;
+        commit(
+            &repo,
+            "master",
+            &[second, side],
+            &[("a.txt", "one\n2\nthree\n")],
+        )?;
+
+        let surf = git::Repository::new(tmp_dir.path())?;
+        let mut browser = git::Browser::new(&surf, git::Branch::local("master"))?;
+        let contributors = contributors(&mut browser, &repo, None::<Revision<String>>)?;
+
+        assert_eq!(contributors.len(), 1);
+        assert_eq!(contributors[0].additions, 1);
+        assert_eq!(contributors[0].deletions, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        // 2019-12-02 10:43:45 UTC, a Monday, and 2019-12-08 23:59:59 UTC, the Sunday after.
+        for time in &[1_575_283_425_i64, 1_575_849_599] {
+            let start = time - (time - FIRST_MONDAY).rem_euclid(WEEK);
+            // 2019-12-02 00:00:00 UTC
+            assert_eq!(start, 1_575_244_800);
+        }
+    }
+}
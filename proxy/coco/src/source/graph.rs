@@ -0,0 +1,322 @@
+//! Commit graphs spanning several revisions, laid out in lanes for drawing how branches diverge.
+
+use std::{collections::HashMap, str::FromStr as _};
+
+use serde::Serialize;
+
+use librad::peer::PeerId;
+use radicle_surf::vcs::git::{self, git2, Browser};
+
+use super::{
+    resolve, sort_refs, strip_namespace, tag_details, tags, Branch, BranchRef, CommitHeader, Error,
+    Revision, Tag,
+};
+use crate::oid::Oid;
+
+/// The number of commits in a [`Graph`] if no limit is given.
+pub const DEFAULT_LIMIT: usize = 500;
+
+/// The history of one or more revisions, newest first, with parents always following their
+/// children.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Graph {
+    /// The commits of the graph in topological order.
+    pub nodes: Vec<Node>,
+    /// The number of lanes needed to draw the graph.
+    pub lanes: usize,
+}
+
+/// A commit in a [`Graph`].
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Node {
+    /// The commit itself.
+    pub header: CommitHeader,
+    /// The lane the commit is drawn in.
+    pub lane: usize,
+    /// The parents of the commit, in the order they are recorded in the commit.
+    pub parents: Vec<Edge>,
+    /// The branches whose tip is the commit.
+    pub branches: Vec<BranchRef>,
+    /// The tags pointing to the commit.
+    pub tags: Vec<Tag>,
+}
+
+/// The line from a [`Node`] to one of its parents.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Edge {
+    /// The parent commit.
+    pub sha1: Oid,
+    /// The lane the line runs in until it reaches the parent.
+    pub lane: usize,
+}
+
+/// Builds the [`Graph`] of the history of all `revisions`, or of the current head of the
+/// `browser` if there are none. At most `limit` commits are included, [`DEFAULT_LIMIT`] if none is
+/// given, parents of the last ones may be missing from the graph.
+///
+/// # Errors
+///
+/// Will return [`Error`] if a revision can't be resolved or the history can't be walked.
+pub fn graph<P>(
+    browser: &mut Browser<'_>,
+    repo: &git2::Repository,
+    revisions: Vec<Revision<P>>,
+    limit: Option<usize>,
+) -> Result<Graph, Error>
+where
+    P: ToString,
+{
+    let heads = if revisions.is_empty() {
+        vec![browser.get().first().id]
+    } else {
+        revisions
+            .into_iter()
+            .map(|revision| resolve(browser, revision))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut tagged: HashMap<git2::Oid, Vec<Tag>> = HashMap::new();
+    for tag in tags(browser)? {
+        if let Some(details) = tag_details(repo, tag).map_err(git::error::Error::from)? {
+            tagged
+                .entry(details.target.sha1.into())
+                .or_default()
+                .push(details.name);
+        }
+    }
+
+    let mut tips = branch_tips(repo).map_err(git::error::Error::from)?;
+
+    let mut walk = repo.revwalk().map_err(git::error::Error::from)?;
+    walk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)
+        .map_err(git::error::Error::from)?;
+    for head in heads {
+        walk.push(head).map_err(git::error::Error::from)?;
+    }
+
+    let mut lanes = Lanes::default();
+    let mut nodes = vec![];
+
+    for oid in walk.take(limit.unwrap_or(DEFAULT_LIMIT)) {
+        let oid = oid.map_err(git::error::Error::from)?;
+        let commit = repo.find_commit(oid).map_err(git::error::Error::from)?;
+        let (lane, parents) = lanes.place(oid, &commit.parent_ids().collect::<Vec<_>>());
+
+        let mut branches = tips.remove(&oid).unwrap_or_default();
+        sort_refs(&mut branches, |branch| (&branch.peer_id, &branch.name));
+
+        let mut tags = tagged.remove(&oid).unwrap_or_default();
+        tags.sort();
+
+        nodes.push(Node {
+            header: CommitHeader::from(&commit),
+            lane,
+            parents,
+            branches,
+            tags,
+        });
+    }
+
+    Ok(Graph {
+        nodes,
+        lanes: lanes.count,
+    })
+}
+
+/// Groups the branches of all peers by the commit they point to.
+fn branch_tips(repo: &git2::Repository) -> Result<HashMap<git2::Oid, Vec<BranchRef>>, git2::Error> {
+    let mut tips: HashMap<git2::Oid, Vec<BranchRef>> = HashMap::new();
+
+    for glob in &["refs/heads/*", "refs/remotes/*/heads/*"] {
+        for reference in repo.references_glob(glob)? {
+            let reference = reference?;
+            let name = match reference.name() {
+                Some(name) => strip_namespace(name),
+                None => continue,
+            };
+            let target = match reference.peel_to_commit() {
+                Ok(commit) => commit.id(),
+                // Refs of other peers may point to missing objects or to no commit at all.
+                Err(_err) => continue,
+            };
+
+            let branch = match name.strip_prefix("refs/heads/") {
+                Some(name) => BranchRef {
+                    name: Branch(name.to_string()),
+                    peer_id: None,
+                },
+                None => {
+                    let mut parts = name.trim_start_matches("refs/remotes/").splitn(3, '/');
+                    let peer_id = parts
+                        .next()
+                        .and_then(|peer_id| PeerId::from_str(peer_id).ok());
+                    let name = parts.nth(1).unwrap_or_default();
+                    BranchRef {
+                        name: Branch(name.to_string()),
+                        peer_id,
+                    }
+                },
+            };
+            tips.entry(target).or_default().push(branch);
+        }
+    }
+
+    Ok(tips)
+}
+
+/// The lanes of a graph while it is being laid out, each waiting for the commit the line running
+/// in it leads to.
+#[derive(Default)]
+struct Lanes {
+    /// The commit each lane waits for, `None` if the lane is free.
+    waiting: Vec<Option<git2::Oid>>,
+    /// The number of lanes used so far.
+    count: usize,
+}
+
+impl Lanes {
+    /// Places the commit `oid` in a lane and routes the lines to its `parents`.
+    fn place(&mut self, oid: git2::Oid, parents: &[git2::Oid]) -> (usize, Vec<Edge>) {
+        let lane = self
+            .waiting
+            .iter()
+            .position(|waiting| *waiting == Some(oid))
+            .unwrap_or_else(|| self.free());
+
+        // Lines of other children converge here.
+        for waiting in &mut self.waiting {
+            if *waiting == Some(oid) {
+                *waiting = None;
+            }
+        }
+
+        let mut edges = Vec::with_capacity(parents.len());
+        for (idx, parent) in parents.iter().enumerate() {
+            let parent_lane = if idx == 0 {
+                lane
+            } else {
+                self.waiting
+                    .iter()
+                    .position(|waiting| *waiting == Some(*parent))
+                    .unwrap_or_else(|| self.free())
+            };
+            if let Some(waiting) = self.waiting.get_mut(parent_lane) {
+                *waiting = Some(*parent);
+            }
+            edges.push(Edge {
+                sha1: (*parent).into(),
+                lane: parent_lane,
+            });
+        }
+
+        (lane, edges)
+    }
+
+    /// Finds the leftmost free lane, opening a new one if all are taken.
+    fn free(&mut self) -> usize {
+        let lane = self
+            .waiting
+            .iter()
+            .position(Option::is_none)
+            .unwrap_or_else(|| {
+                self.waiting.push(None);
+                self.waiting.len() - 1
+            });
+        self.count = self.count.max(lane + 1);
+
+        lane
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use radicle_surf::vcs::git::{self, git2};
+
+    use super::{graph, Lanes, Revision};
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    fn branches_label_their_tips_only() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let repo = git2::Repository::init(tmp_dir.path())?;
+        let signature = git2::Signature::now("Jane Doe", "jane@example.com")?;
+        let tree = repo.find_tree(repo.treebuilder(None)?.write()?)?;
+        let first = repo.commit(
+            Some("refs/heads/dev"),
+            &signature,
+            &signature,
+            "first",
+            &tree,
+            &[],
+        )?;
+        let first = repo.find_commit(first)?;
+        repo.commit(
+            Some("refs/heads/master"),
+            &signature,
+            &signature,
+            "second",
+            &tree,
+            &[&first],
+        )?;
+
+        let surf = git::Repository::new(tmp_dir.path())?;
+        let mut browser = git::Browser::new(&surf, git::Branch::local("master"))?;
+        let names = |branches: &[super::BranchRef]| {
+            branches
+                .iter()
+                .map(|branch| branch.name.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let all = graph(&mut browser, &repo, Vec::<Revision<String>>::new(), None)?;
+        assert_eq!(all.nodes.len(), 2);
+        assert_eq!(names(&all.nodes[0].branches), vec!["master"]);
+        assert_eq!(names(&all.nodes[1].branches), vec!["dev"]);
+
+        // Master contains the tip of dev, but its own tip is not part of the graph.
+        let dev = graph(
+            &mut browser,
+            &repo,
+            vec![Revision::Branch {
+                name: "dev".to_string(),
+                peer_id: None,
+            }],
+            None,
+        )?;
+        assert_eq!(dev.nodes.len(), 1);
+        assert_eq!(names(&dev.nodes[0].branches), vec!["dev"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn merged_branches_share_lanes() -> Result<(), git2::Error> {
+        let oid = |n: u8| git2::Oid::from_bytes(&[n; 20]);
+        let (merge, left, right, base) = (oid(4)?, oid(3)?, oid(2)?, oid(1)?);
+        let lanes_of = |edges: Vec<super::Edge>| edges.iter().map(|e| e.lane).collect::<Vec<_>>();
+
+        let mut lanes = Lanes::default();
+
+        let (lane, edges) = lanes.place(merge, &[left, right]);
+        assert_eq!((lane, lanes_of(edges)), (0, vec![0, 1]));
+
+        let (lane, edges) = lanes.place(left, &[base]);
+        assert_eq!((lane, lanes_of(edges)), (0, vec![0]));
+
+        let (lane, edges) = lanes.place(right, &[base]);
+        assert_eq!((lane, lanes_of(edges)), (1, vec![1]));
+
+        // Both lines end at the base, which frees the second lane.
+        let (lane, edges) = lanes.place(base, &[]);
+        assert_eq!((lane, lanes_of(edges)), (0, vec![]));
+        assert_eq!(lanes.waiting, vec![None, None]);
+        assert_eq!(lanes.count, 2);
+
+        Ok(())
+    }
+}
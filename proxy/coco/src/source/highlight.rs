@@ -0,0 +1,415 @@
+//! Syntax highlighting of blobs, with syntaxes and themes loaded once and the rendered HTML
+//! cached.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    path,
+    sync::{Mutex, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+use syntect::{
+    easy::HighlightLines,
+    highlighting::ThemeSet,
+    parsing::{ParseState, ScopeStack, SyntaxReference, SyntaxSet},
+    util::LinesWithEndings,
+};
+
+use radicle_surf::vcs::git::git2;
+
+use super::Error;
+
+/// Upper bound for the accumulated size of the HTML kept in the cache.
+const CACHE_CAPACITY: usize = 32 * 1024 * 1024;
+
+lazy_static::lazy_static! {
+    // Syntaxes and themes are slow to load (~30ms), so we make sure to only load them once.
+    // It _will_ affect the latency of the first request that uses syntax highlighting,
+    // but this is acceptable for now.
+    static ref HIGHLIGHTER: RwLock<Highlighter> = RwLock::new(Highlighter {
+        syntaxes: default_syntaxes(),
+        themes: ThemeSet::load_defaults(),
+    });
+
+    static ref CACHE: Mutex<Cache> = Mutex::new(Cache::default());
+}
+
+/// The syntaxes and themes available for highlighting.
+struct Highlighter {
+    /// Known syntaxes, looked up by file extension.
+    syntaxes: SyntaxSet,
+    /// Known themes, looked up by name.
+    themes: ThemeSet,
+}
+
+/// Identifies rendered HTML by the blob oid and the names of the syntax and theme it was
+/// highlighted with.
+type Key = (git2::Oid, String, String);
+
+/// Rendered HTML keyed by [`Key`]. Once the capacity is exceeded the oldest entries are evicted
+/// first.
+#[derive(Default)]
+struct Cache {
+    /// The rendered HTML.
+    entries: HashMap<Key, String>,
+    /// Keys of the entries in order of insertion.
+    order: VecDeque<Key>,
+    /// Accumulated size of the entries.
+    size: usize,
+}
+
+impl Cache {
+    /// Stores the `html` for the `key`, evicting old entries to make room.
+    fn insert(&mut self, key: Key, html: String) {
+        if html.len() > CACHE_CAPACITY || self.entries.contains_key(&key) {
+            return;
+        }
+
+        while self.size + html.len() > CACHE_CAPACITY {
+            match self.order.pop_front() {
+                Some(oldest) => {
+                    if let Some(evicted) = self.entries.remove(&oldest) {
+                        self.size -= evicted.len();
+                    }
+                },
+                None => break,
+            }
+        }
+
+        self.size += html.len();
+        self.order.push_back(key.clone());
+        self.entries.insert(key, html);
+    }
+
+    /// Drops all entries.
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+        self.size = 0;
+    }
+}
+
+/// Loads the default syntaxes together with the ones shipped in the `assets` directory.
+fn default_syntaxes() -> SyntaxSet {
+    let default_set = SyntaxSet::load_defaults_newlines();
+    let mut builder = default_set.into_builder();
+
+    if cfg!(debug_assertions) {
+        // In development assets are relative to the proxy source.
+        // Don't crash if we aren't able to load additional syntaxes for some reason.
+        builder.add_from_folder("./assets", true).ok();
+    } else {
+        // In production assets are relative to the proxy executable.
+        let exe_path = std::env::current_exe().expect("Can't get current exe path");
+        let root_path = exe_path
+            .parent()
+            .expect("Could not get parent path of current executable");
+        let mut tmp = root_path.to_path_buf();
+        tmp.push("assets");
+        let asset_path = tmp.to_str().expect("Couldn't convert pathbuf to str");
+
+        // Don't crash if we aren't able to load additional syntaxes for some reason.
+        match builder.add_from_folder(asset_path, true) {
+            Ok(_) => (),
+            Err(err) => log::warn!("Syntax builder error : {}", err),
+        };
+    }
+    builder.build()
+}
+
+/// Adds the `.sublime-syntax` and `.tmTheme` files found in `folder` to the available syntaxes
+/// and themes. Previously rendered HTML and language breakdowns are discarded, as they depend on
+/// the syntaxes.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the folder can't be read or one of the files fails to parse.
+pub fn load_from_folder(folder: impl AsRef<path::Path>) -> Result<(), Error> {
+    let folder = folder.as_ref();
+    let mut highlighter = HIGHLIGHTER.write().expect("highlighter lock poisoned");
+
+    let mut builder = highlighter.syntaxes.clone().into_builder();
+    builder.add_from_folder(folder, true)?;
+    highlighter.themes.add_from_folder(folder)?;
+    highlighter.syntaxes = builder.build();
+    CACHE.lock().expect("highlight cache lock poisoned").clear();
+    super::languages::clear_cache();
+
+    Ok(())
+}
+
+/// Lists the names of the available themes in alphabetical order.
+#[must_use]
+pub fn themes() -> Vec<String> {
+    // `ThemeSet` stores its themes in a `BTreeMap`, so they are already sorted.
+    HIGHLIGHTER
+        .read()
+        .expect("highlighter lock poisoned")
+        .themes
+        .themes
+        .keys()
+        .cloned()
+        .collect()
+}
+
+/// Highlights the `content` of the file at `path`, stored as the blob `oid`, with the theme
+/// `theme_name`.
+///
+/// Returns `None` if there is no syntax for the file extension or no theme with that name.
+#[must_use]
+pub fn html(path: &str, oid: git2::Oid, content: &str, theme_name: &str) -> Option<String> {
+    let highlighter = HIGHLIGHTER.read().expect("highlighter lock poisoned");
+    let syntax = find_syntax(&highlighter.syntaxes, path)?;
+    let theme = highlighter.themes.themes.get(theme_name)?;
+
+    let key = (oid, syntax.name.clone(), theme_name.to_string());
+    if let Some(html) = CACHE
+        .lock()
+        .expect("highlight cache lock poisoned")
+        .entries
+        .get(&key)
+    {
+        return Some(html.clone());
+    }
+
+    let mut lines = HighlightLines::new(syntax, theme);
+    let mut html = String::with_capacity(content.len());
+
+    for line in LinesWithEndings::from(content) {
+        let regions = lines.highlight(line, &highlighter.syntaxes);
+        syntect::html::append_highlighted_html_for_styled_line(
+            &regions[..],
+            syntect::html::IncludeBackground::No,
+            &mut html,
+        );
+    }
+
+    CACHE
+        .lock()
+        .expect("highlight cache lock poisoned")
+        .insert(key, html.clone());
+
+    Some(html)
+}
+
+/// A range of lines, numbered from 1 with both ends inclusive. Open ends extend to the start and
+/// end of the file.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineRange {
+    /// The first line to include.
+    pub start: Option<usize>,
+    /// The last line to include.
+    pub end: Option<usize>,
+}
+
+impl LineRange {
+    /// Indicates if the line `number` lies within the range.
+    fn contains(self, number: usize) -> bool {
+        self.start.map_or(true, |start| start <= number)
+            && self.end.map_or(true, |end| number <= end)
+    }
+
+    /// Indicates if the line `number` lies after the range.
+    fn is_past(self, number: usize) -> bool {
+        self.end.map_or(false, |end| number > end)
+    }
+}
+
+/// A line of highlighted content.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Line {
+    /// The line number in the file, starting from 1.
+    pub number: usize,
+    /// The tokens making up the line, without its line ending.
+    pub tokens: Vec<Token>,
+}
+
+/// A piece of a line sharing the same scopes.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Token {
+    /// The stack of scopes the text belongs to, outermost first, e.g.
+    /// `["source.rust", "meta.function.rust", "keyword.other.fn.rust"]`.
+    pub scopes: Vec<String>,
+    /// The text of the token.
+    pub text: String,
+}
+
+/// Splits the `content` of the file at `path` into scoped [`Token`]s line by line. Files without
+/// a known syntax are treated as plain text. Only the lines within `range` are returned, though
+/// everything before has to be parsed to get there.
+#[must_use]
+pub fn tokens(path: &str, content: &str, range: LineRange) -> Vec<Line> {
+    let highlighter = HIGHLIGHTER.read().expect("highlighter lock poisoned");
+    let syntaxes = &highlighter.syntaxes;
+    let syntax = find_syntax(syntaxes, path).unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+
+    let mut state = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+    let mut lines = vec![];
+
+    for (idx, line) in LinesWithEndings::from(content).enumerate() {
+        let number = idx + 1;
+        if range.is_past(number) {
+            break;
+        }
+
+        let ops = state.parse_line(line, syntaxes);
+        let text = line.trim_end_matches(|c| c == '\n' || c == '\r');
+        let mut tokens = vec![];
+        let mut offset = 0;
+
+        for (position, op) in ops {
+            let position = position.min(text.len());
+            if position > offset {
+                tokens.push(token(&stack, &text[offset..position]));
+                offset = position;
+            }
+            stack.apply(&op);
+        }
+        if offset < text.len() {
+            tokens.push(token(&stack, &text[offset..]));
+        }
+
+        if range.contains(number) {
+            lines.push(Line { number, tokens });
+        }
+    }
+
+    lines
+}
+
+/// Builds a [`Token`] for `text` with the scopes currently on the `stack`.
+fn token(stack: &ScopeStack, text: &str) -> Token {
+    Token {
+        scopes: stack
+            .as_slice()
+            .iter()
+            .map(syntect::parsing::Scope::build_string)
+            .collect(),
+        text: text.to_string(),
+    }
+}
+
+/// Detects the language of the file at `path` from its extension. Returns `None` for unknown
+/// extensions and plain text.
+#[must_use]
+pub fn language(path: &str) -> Option<String> {
+    let highlighter = HIGHLIGHTER.read().expect("highlighter lock poisoned");
+    let syntax = find_syntax(&highlighter.syntaxes, path)?;
+
+    if syntax.scope == highlighter.syntaxes.find_syntax_plain_text().scope {
+        None
+    } else {
+        Some(syntax.name.clone())
+    }
+}
+
+/// Looks up the syntax for the file at `path` by its extension.
+fn find_syntax<'a>(syntaxes: &'a SyntaxSet, path: &str) -> Option<&'a SyntaxReference> {
+    path::Path::new(path)
+        .extension()
+        .and_then(std::ffi::OsStr::to_str)
+        .and_then(|ext| syntaxes.find_syntax_by_extension(ext))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{html, language, themes, tokens, Cache, LineRange, Token, CACHE_CAPACITY};
+
+    #[test]
+    fn default_themes() {
+        let themes = themes();
+        assert!(themes.contains(&"base16-ocean.dark".to_string()));
+        assert!(themes.contains(&"base16-ocean.light".to_string()));
+    }
+
+    #[test]
+    fn languages_by_extension() {
+        assert_eq!(language("src/lib.rs"), Some("Rust".to_string()));
+        assert_eq!(language("src/Eval.hs"), Some("Haskell".to_string()));
+        assert_eq!(language("text/arrows.txt"), None);
+        assert_eq!(language("bin/ls"), None);
+    }
+
+    #[test]
+    fn cache_evicts_oldest() {
+        let mut cache = Cache::default();
+        let half = "x".repeat(CACHE_CAPACITY / 2);
+        let key = |n: u8| {
+            (
+                radicle_surf::vcs::git::git2::Oid::from_bytes(&[n; 20]).expect("invalid oid"),
+                "Rust".to_string(),
+                "theme".to_string(),
+            )
+        };
+
+        cache.insert(key(1), half.clone());
+        cache.insert(key(2), half.clone());
+        cache.insert(key(3), half);
+
+        assert_eq!(cache.entries.len(), 2);
+        assert!(!cache.entries.contains_key(&key(1)));
+        assert_eq!(cache.size, CACHE_CAPACITY);
+    }
+
+    #[test]
+    fn html_depends_on_syntax() {
+        let content = "fn main() {}\n";
+        let oid = radicle_surf::vcs::git::git2::Oid::hash_object(
+            radicle_surf::vcs::git::git2::ObjectType::Blob,
+            content.as_bytes(),
+        )
+        .expect("failed to hash content");
+        let rust = html("main.rs", oid, content, "base16-ocean.dark");
+        let text = html("main.txt", oid, content, "base16-ocean.dark");
+
+        assert!(rust.is_some());
+        assert!(text.is_some());
+        assert_ne!(rust, text);
+        assert_eq!(html("main.rs", oid, content, "base16-ocean.dark"), rust);
+    }
+
+    #[test]
+    fn tokens_in_range() {
+        let content = "fn main() {\n    let x = 1;\n}\n";
+        let range = LineRange {
+            start: Some(2),
+            end: Some(2),
+        };
+        let lines = tokens("main.rs", content, range);
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].number, 2);
+        assert_eq!(
+            lines[0]
+                .tokens
+                .iter()
+                .map(|token| token.text.as_str())
+                .collect::<String>(),
+            "    let x = 1;"
+        );
+        assert!(lines[0]
+            .tokens
+            .iter()
+            .all(|token| token.scopes.first() == Some(&"source.rust".to_string())));
+    }
+
+    #[test]
+    fn tokens_plain_text() {
+        let lines = tokens("notes", "hello\nworld", LineRange::default());
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[1].tokens,
+            vec![Token {
+                scopes: vec!["text.plain".to_string()],
+                text: "world".to_string(),
+            }]
+        );
+    }
+}
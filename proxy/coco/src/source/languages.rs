@@ -0,0 +1,173 @@
+//! Breakdown of the languages a revision is written in.
+
+use std::{collections::HashMap, convert::TryFrom as _, sync::Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use radicle_surf::vcs::git::{self, git2, Browser, Rev};
+
+use super::{highlight, Error, Revision};
+
+/// Maximum number of revisions to keep breakdowns for.
+const CACHE_CAPACITY: usize = 1024;
+
+lazy_static::lazy_static! {
+    // The breakdown only depends on the tree of a commit, so it is computed once per head.
+    static ref CACHE: Mutex<HashMap<git2::Oid, Vec<Language>>> = Mutex::new(HashMap::new());
+}
+
+/// The share of a single language in a revision.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Language {
+    /// Name of the language, as known to the syntax highlighter.
+    pub name: String,
+    /// Accumulated size of all files in the language.
+    pub bytes: u64,
+    /// Share of the language in the size of all files with a known language, from 0 to 100.
+    pub percentage: f64,
+}
+
+/// Breaks the tree of the `revision` down into the languages its files are written in, detected
+/// by their extension. Files in unknown languages or plain text are left out. Languages are
+/// ordered by size, largest first.
+///
+/// # Errors
+///
+/// Will return [`Error`] if a surf or git interaction fails.
+pub fn languages<P>(
+    browser: &mut Browser<'_>,
+    repo: &git2::Repository,
+    maybe_revision: Option<Revision<P>>,
+) -> Result<Vec<Language>, Error>
+where
+    P: ToString,
+{
+    let maybe_revision = maybe_revision.map(Rev::try_from).transpose()?;
+    if let Some(revision) = maybe_revision {
+        browser.rev(revision)?;
+    }
+
+    let head = browser.get().first().id;
+    if let Some(languages) = CACHE
+        .lock()
+        .expect("languages cache lock poisoned")
+        .get(&head)
+    {
+        return Ok(languages.clone());
+    }
+
+    let languages = breakdown(repo, head).map_err(git::error::Error::from)?;
+
+    let mut cache = CACHE.lock().expect("languages cache lock poisoned");
+    if cache.len() >= CACHE_CAPACITY {
+        cache.clear();
+    }
+    cache.insert(head, languages.clone());
+
+    Ok(languages)
+}
+
+/// Drops all cached breakdowns, e.g. after the known syntaxes changed.
+pub(super) fn clear_cache() {
+    CACHE.lock().expect("languages cache lock poisoned").clear();
+}
+
+/// Computes the breakdown for the tree of the commit `head`.
+fn breakdown(repo: &git2::Repository, head: git2::Oid) -> Result<Vec<Language>, git2::Error> {
+    let odb = repo.odb()?;
+    let mut sizes: HashMap<String, u64> = HashMap::new();
+
+    for (path, oid) in super::search::blobs(repo, head)? {
+        let name = match highlight::language(&path) {
+            Some(name) => name,
+            None => continue,
+        };
+        let (size, _kind) = odb.read_header(oid)?;
+        *sizes.entry(name).or_default() += size as u64;
+    }
+
+    Ok(shares(sizes))
+}
+
+/// Turns the accumulated `sizes` per language into [`Language`]s, largest first. If all files are
+/// empty, every language has a share of 0.
+fn shares(sizes: HashMap<String, u64>) -> Vec<Language> {
+    let total = sizes.values().sum::<u64>();
+    #[allow(clippy::cast_precision_loss)]
+    let mut languages = sizes
+        .into_iter()
+        .map(|(name, bytes)| Language {
+            name,
+            bytes,
+            percentage: if total == 0 {
+                0.0
+            } else {
+                bytes as f64 * 100.0 / total as f64
+            },
+        })
+        .collect::<Vec<_>>();
+    languages.sort_by(|left, right| {
+        right
+            .bytes
+            .cmp(&left.bytes)
+            .then_with(|| left.name.cmp(&right.name))
+    });
+
+    languages
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{shares, Language};
+
+    #[test]
+    fn shares_by_size() {
+        let sizes = vec![("Rust".to_string(), 3), ("Haskell".to_string(), 1)]
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        assert_eq!(
+            shares(sizes),
+            vec![
+                Language {
+                    name: "Rust".to_string(),
+                    bytes: 3,
+                    percentage: 75.0,
+                },
+                Language {
+                    name: "Haskell".to_string(),
+                    bytes: 1,
+                    percentage: 25.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_files_have_no_share() {
+        let sizes = vec![("Rust".to_string(), 0), ("Haskell".to_string(), 0)]
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        assert_eq!(
+            shares(sizes),
+            vec![
+                Language {
+                    name: "Haskell".to_string(),
+                    bytes: 0,
+                    percentage: 0.0,
+                },
+                Language {
+                    name: "Rust".to_string(),
+                    bytes: 0,
+                    percentage: 0.0,
+                },
+            ]
+        );
+    }
+}
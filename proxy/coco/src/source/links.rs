@@ -0,0 +1,248 @@
+//! Submodules and symbolic links, which surf doesn't tell apart from directories and files.
+
+use std::{collections::HashMap, path};
+
+use serde::Serialize;
+
+use radicle_surf::vcs::git::git2;
+
+use super::ObjectType;
+use crate::oid::Oid;
+
+/// Mode git records for submodules, also known as gitlinks.
+const SUBMODULE_MODE: i32 = 0o160_000;
+
+/// Mode git records for symbolic links.
+const SYMLINK_MODE: i32 = 0o120_000;
+
+/// A repository embedded at a fixed commit.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Submodule {
+    /// The commit of the embedded repository the submodule is pinned to.
+    pub commit: Oid,
+    /// Where to fetch the embedded repository from, as configured in `.gitmodules`.
+    pub url: Option<String>,
+}
+
+/// A symbolic link.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Symlink {
+    /// The path the link points to, relative to the directory of the link unless absolute.
+    pub target: String,
+}
+
+/// Finds the submodules and symbolic links among the entries of the directory under `prefix`, or
+/// the root directory if it is `None`, in the tree of the commit `head`. They are keyed by their
+/// name.
+pub(super) fn entries(
+    repo: &git2::Repository,
+    head: git2::Oid,
+    prefix: Option<&path::Path>,
+) -> Result<HashMap<String, ObjectType>, git2::Error> {
+    let root = repo.find_commit(head)?.tree()?;
+    let dir = match prefix {
+        None => root.clone(),
+        Some(prefix) => repo.find_tree(root.get_path(prefix)?.id())?,
+    };
+
+    let mut urls = None;
+    let mut entries = HashMap::new();
+    for entry in dir.iter() {
+        let name = match entry.name() {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        let path = match prefix {
+            None => name.clone(),
+            Some(prefix) => format!("{}/{}", prefix.display(), name),
+        };
+
+        if let Some(object_type) = object_type(repo, &root, &entry, &path, &mut urls)? {
+            entries.insert(name, object_type);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Looks up the entry under `path` in the tree of the commit `head`. Returns its [`ObjectType`]
+/// if it is a submodule or symbolic link, `None` otherwise, including when there is no such entry.
+pub(super) fn find(
+    repo: &git2::Repository,
+    head: git2::Oid,
+    path: &str,
+) -> Result<Option<ObjectType>, git2::Error> {
+    let root = repo.find_commit(head)?.tree()?;
+    let entry = match root.get_path(path::Path::new(path.trim_matches('/'))) {
+        Ok(entry) => entry,
+        Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    object_type(repo, &root, &entry, path.trim_matches('/'), &mut None)
+}
+
+/// Determines the [`ObjectType`] of a submodule or symbolic link `entry` located at `path`.
+/// The submodule URLs are read from the `root` tree on first use and kept in `urls`.
+fn object_type(
+    repo: &git2::Repository,
+    root: &git2::Tree<'_>,
+    entry: &git2::TreeEntry<'_>,
+    path: &str,
+    urls: &mut Option<HashMap<String, String>>,
+) -> Result<Option<ObjectType>, git2::Error> {
+    match entry.filemode() {
+        SUBMODULE_MODE => {
+            if urls.is_none() {
+                *urls = Some(submodule_urls(repo, root)?);
+            }
+            let url = urls.as_ref().and_then(|urls| urls.get(path)).cloned();
+
+            Ok(Some(ObjectType::Submodule(Submodule {
+                commit: entry.id().into(),
+                url,
+            })))
+        },
+        SYMLINK_MODE => {
+            let blob = repo.find_blob(entry.id())?;
+
+            Ok(Some(ObjectType::Symlink(Symlink {
+                target: String::from_utf8_lossy(blob.content()).to_string(),
+            })))
+        },
+        _ => Ok(None),
+    }
+}
+
+/// Reads the URLs of the submodules from the `.gitmodules` file in the `root` tree, keyed by the
+/// path of the submodule.
+fn submodule_urls(
+    repo: &git2::Repository,
+    root: &git2::Tree<'_>,
+) -> Result<HashMap<String, String>, git2::Error> {
+    let entry = match root.get_name(".gitmodules") {
+        Some(entry) => entry,
+        None => return Ok(HashMap::new()),
+    };
+    let blob = repo.find_blob(entry.id())?;
+
+    Ok(parse_gitmodules(&String::from_utf8_lossy(blob.content())))
+}
+
+/// Parses the contents of a `.gitmodules` file into the URLs of the submodules, keyed by their
+/// path. Sections without both a path and a URL are left out.
+fn parse_gitmodules(content: &str) -> HashMap<String, String> {
+    let mut urls = HashMap::new();
+    let mut section: (Option<String>, Option<String>) = (None, None);
+
+    let mut flush = |section: &mut (Option<String>, Option<String>)| {
+        if let (Some(path), Some(url)) = (section.0.take(), section.1.take()) {
+            urls.insert(path, url);
+        }
+    };
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            flush(&mut section);
+            continue;
+        }
+        if line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        let mut parts = line.splitn(2, '=');
+        let key = parts.next().unwrap_or_default().trim();
+        let value = match parts.next() {
+            Some(value) => value.trim().trim_matches('"').to_string(),
+            None => continue,
+        };
+        match key {
+            "path" => section.0 = Some(value.trim_matches('/').to_string()),
+            "url" => section.1 = Some(value),
+            _ => {},
+        }
+    }
+    flush(&mut section);
+
+    urls
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use radicle_surf::vcs::git::git2;
+
+    use super::{
+        entries, find, parse_gitmodules, ObjectType, Submodule, Symlink, SUBMODULE_MODE,
+        SYMLINK_MODE,
+    };
+
+    #[test]
+    fn submodules_and_symlinks() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let repo = git2::Repository::init(tmp_dir.path())?;
+        let signature = git2::Signature::now("Jane Doe", "jane@example.com")?;
+
+        let empty = repo.find_tree(repo.treebuilder(None)?.write()?)?;
+        let pinned = repo.commit(None, &signature, &signature, "pinned", &empty, &[])?;
+
+        let gitmodules = "[submodule \"lib\"]\n\tpath = lib\n\turl = https://example.com/lib.git\n";
+        let mut builder = repo.treebuilder(None)?;
+        builder.insert(".gitmodules", repo.blob(gitmodules.as_bytes())?, 0o100_644)?;
+        builder.insert("README", repo.blob(b"hello")?, 0o100_644)?;
+        builder.insert("lib", pinned, SUBMODULE_MODE)?;
+        builder.insert("link", repo.blob(b"README")?, SYMLINK_MODE)?;
+        let tree = repo.find_tree(builder.write()?)?;
+        let head = repo.commit(None, &signature, &signature, "head", &tree, &[])?;
+
+        let submodule = ObjectType::Submodule(Submodule {
+            commit: pinned.into(),
+            url: Some("https://example.com/lib.git".to_string()),
+        });
+        let symlink = ObjectType::Symlink(Symlink {
+            target: "README".to_string(),
+        });
+
+        let links = entries(&repo, head, None)?;
+        assert_eq!(links.len(), 2);
+        assert_eq!(links.get("lib"), Some(&submodule));
+        assert_eq!(links.get("link"), Some(&symlink));
+
+        assert_eq!(find(&repo, head, "lib")?, Some(submodule));
+        assert_eq!(find(&repo, head, "link")?, Some(symlink));
+        assert_eq!(find(&repo, head, "README")?, None);
+        assert_eq!(find(&repo, head, "missing")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_submodule_urls() {
+        let content = r#"
+[submodule "vendor/lib"]
+	path = vendor/lib
+	url = https://example.com/lib.git
+[submodule "docs"]
+	url = "git@example.com:docs.git"
+	path = docs/
+# A section without a URL.
+[submodule "broken"]
+	path = broken
+"#;
+        let urls = parse_gitmodules(content);
+
+        assert_eq!(urls.len(), 2);
+        assert_eq!(
+            urls.get("vendor/lib").map(String::as_str),
+            Some("https://example.com/lib.git")
+        );
+        assert_eq!(
+            urls.get("docs").map(String::as_str),
+            Some("git@example.com:docs.git")
+        );
+    }
+}
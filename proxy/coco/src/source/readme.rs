@@ -0,0 +1,240 @@
+//! Detection and rendering of the README of a directory.
+
+use std::{borrow::Cow, collections::HashSet, convert::TryFrom as _, str::FromStr as _};
+
+use serde::Serialize;
+
+use radicle_surf::{
+    file_system,
+    vcs::git::{Browser, Rev},
+};
+
+use super::{Error, Revision};
+
+/// File names recognised as README, compared case-insensitively, in order of preference.
+const NAMES: [&str; 5] = [
+    "readme.md",
+    "readme.markdown",
+    "readme.rst",
+    "readme.txt",
+    "readme",
+];
+
+/// A README rendered to HTML.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Readme {
+    /// Absolute path to the file from the root of the repo.
+    pub path: String,
+    /// Sanitised HTML, safe to be embedded into a page.
+    pub html: String,
+}
+
+/// What a relative link or image source of a README points to, as a decoded path from the root
+/// of the repo.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Link<'a> {
+    /// A directory of the repo.
+    Directory(&'a str),
+    /// Anything else, usually a file.
+    File(&'a str),
+}
+
+/// Finds the README in the directory `prefix` at `revision` and renders it to sanitised HTML.
+/// Markdown is rendered as such, any other format as preformatted text.
+///
+/// Relative links and image sources are resolved against the directory of the README and passed
+/// to `url`, which turns the resulting [`Link`] into a URL, e.g. to the tree of a directory or
+/// to the raw file.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the directory doesn't exist or a surf interaction fails.
+pub fn readme<P, F>(
+    browser: &mut Browser<'_>,
+    maybe_revision: Option<Revision<P>>,
+    maybe_prefix: Option<String>,
+    url: F,
+) -> Result<Option<Readme>, Error>
+where
+    P: ToString,
+    F: Fn(Link<'_>) -> String + Send + Sync + 'static,
+{
+    let maybe_revision = maybe_revision.map(Rev::try_from).transpose()?;
+    if let Some(revision) = maybe_revision {
+        browser.rev(revision)?;
+    }
+
+    let prefix = maybe_prefix
+        .as_deref()
+        .unwrap_or_default()
+        .trim_matches('/')
+        .to_string();
+    let root = browser.get_directory()?;
+    let found;
+    let dir = if prefix.is_empty() {
+        &root
+    } else {
+        let path = file_system::Path::from_str(&prefix)?;
+        found = root
+            .find_directory(path.clone())
+            .ok_or(Error::PathNotFound(path))?;
+        &found
+    };
+
+    let mut files = dir
+        .list_directory()
+        .into_iter()
+        .filter(|(_, system_type)| *system_type == file_system::SystemType::File)
+        .map(|(label, _)| label.to_string())
+        .filter_map(|name| {
+            let rank = NAMES
+                .iter()
+                .position(|candidate| name.eq_ignore_ascii_case(candidate))?;
+            Some((rank, name))
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+
+    let name = match files.into_iter().next() {
+        Some((_, name)) => name,
+        None => return Ok(None),
+    };
+    let path = if prefix.is_empty() {
+        name.clone()
+    } else {
+        format!("{}/{}", prefix, name)
+    };
+
+    let file_path = file_system::Path::from_str(&path)?;
+    let file = dir
+        .find_file(file_system::Path::from_str(&name)?)
+        .ok_or(Error::PathNotFound(file_path))?;
+    let content = String::from_utf8_lossy(&file.contents);
+
+    let lower = name.to_lowercase();
+    let html = if lower.ends_with(".md") || lower.ends_with(".markdown") {
+        let directories = linked_directories(&content, &prefix, &root);
+        render_markdown(&content, prefix, directories, url)
+    } else {
+        format!("<pre>{}</pre>", ammonia::clean_text(&content))
+    };
+
+    Ok(Some(Readme { path, html }))
+}
+
+/// Collects the paths of the directories among the targets of the relative links and images of
+/// the Markdown `content`, resolved against the directory `dir`.
+fn linked_directories(content: &str, dir: &str, root: &file_system::Directory) -> HashSet<String> {
+    pulldown_cmark::Parser::new(content)
+        .filter_map(|event| match event {
+            pulldown_cmark::Event::Start(pulldown_cmark::Tag::Link(_, url, _))
+            | pulldown_cmark::Event::Start(pulldown_cmark::Tag::Image(_, url, _)) => {
+                resolve(dir, &url)
+            },
+            _ => None,
+        })
+        .filter(|path| {
+            path.is_empty()
+                || file_system::Path::from_str(path)
+                    .ok()
+                    .and_then(|path| root.find_directory(path))
+                    .is_some()
+        })
+        .collect()
+}
+
+/// Renders Markdown to HTML and strips everything unsafe from it, rewriting relative URLs on the
+/// way. The resolved paths found in `directories` are linked as such.
+fn render_markdown<F>(content: &str, dir: String, directories: HashSet<String>, url: F) -> String
+where
+    F: Fn(Link<'_>) -> String + Send + Sync + 'static,
+{
+    let mut options = pulldown_cmark::Options::empty();
+    options.insert(pulldown_cmark::Options::ENABLE_TABLES);
+    options.insert(pulldown_cmark::Options::ENABLE_STRIKETHROUGH);
+    options.insert(pulldown_cmark::Options::ENABLE_TASKLISTS);
+    options.insert(pulldown_cmark::Options::ENABLE_FOOTNOTES);
+
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, pulldown_cmark::Parser::new_ext(content, options));
+
+    let rewrite = move |relative: &str| -> Option<Cow<'_, str>> {
+        if relative.starts_with('#') {
+            return Some(Cow::Owned(relative.to_string()));
+        }
+        resolve(&dir, relative).map(|path| {
+            let link = if directories.contains(&path) {
+                Link::Directory(&path)
+            } else {
+                Link::File(&path)
+            };
+            Cow::Owned(url(link))
+        })
+    };
+
+    ammonia::Builder::default()
+        .url_relative(ammonia::UrlRelative::Custom(Box::new(rewrite)))
+        .clean(&html)
+        .to_string()
+}
+
+/// Resolves the relative `url` against the directory `dir`, both relative to the root of the
+/// repo. Leading slashes point at the root of the repo. Query and fragment are dropped and the
+/// path is percent-decoded.
+///
+/// Returns `None` if the path escapes the repo.
+fn resolve(dir: &str, url: &str) -> Option<String> {
+    let url = url
+        .split(|c| c == '?' || c == '#')
+        .next()
+        .unwrap_or_default();
+    let url = percent_encoding::percent_decode_str(url).decode_utf8_lossy();
+    let url: &str = &url;
+    let (base, url) = match url.strip_prefix('/') {
+        Some(url) => ("", url),
+        None => (dir, url),
+    };
+
+    let mut segments = Vec::new();
+    for segment in base.split('/').chain(url.split('/')) {
+        match segment {
+            "" | "." => {},
+            ".." => {
+                segments.pop()?;
+            },
+            segment => segments.push(segment),
+        }
+    }
+
+    Some(segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::resolve;
+
+    #[test]
+    fn resolve_relative_urls() {
+        assert_eq!(resolve("", "logo.png"), Some("logo.png".to_string()));
+        assert_eq!(
+            resolve("docs", "./img/a.png"),
+            Some("docs/img/a.png".to_string())
+        );
+        assert_eq!(
+            resolve("docs/guide", "../a.md#intro"),
+            Some("docs/a.md".to_string())
+        );
+        assert_eq!(
+            resolve("docs", "/src/lib.rs?plain=1"),
+            Some("src/lib.rs".to_string())
+        );
+        assert_eq!(resolve("docs", "../../secret"), None);
+        assert_eq!(
+            resolve("", "my%20notes/100%25.md"),
+            Some("my notes/100%.md".to_string())
+        );
+    }
+}
@@ -0,0 +1,273 @@
+//! Full-text search across the blobs of a project revision.
+
+use std::convert::TryFrom as _;
+
+use serde::{Deserialize, Serialize};
+
+use radicle_surf::vcs::git::{self, git2, Browser, Rev};
+
+use super::{Error, Revision};
+
+/// The default maximum number of matching lines returned by [`search`].
+pub const DEFAULT_LIMIT: usize = 100;
+
+/// The default number of lines surrounding a matching line returned by [`search`].
+pub const DEFAULT_CONTEXT: usize = 2;
+
+/// Blobs larger than this are listed by [`documents`] without their content.
+pub const MAX_DOCUMENT_SIZE: usize = 512 * 1024;
+
+/// What to search for.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum Query {
+    /// Match the text as is.
+    #[serde(rename_all = "camelCase")]
+    Literal {
+        /// The text to look for.
+        text: String,
+    },
+    /// Match a regular expression, following the syntax of the [`regex`] crate.
+    #[serde(rename_all = "camelCase")]
+    Regex {
+        /// The expression to look for.
+        expression: String,
+    },
+}
+
+impl Query {
+    /// Compiles the query into a [`regex::Regex`].
+    fn compile(&self) -> Result<regex::Regex, regex::Error> {
+        match self {
+            Self::Literal { text } => regex::Regex::new(&regex::escape(text)),
+            Self::Regex { expression } => regex::Regex::new(expression),
+        }
+    }
+}
+
+/// Knobs to tune the size of the search [`Results`].
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    /// The maximum number of matching lines.
+    pub limit: usize,
+    /// The number of lines to include before and after each matching line.
+    pub context: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_LIMIT,
+            context: DEFAULT_CONTEXT,
+        }
+    }
+}
+
+/// The outcome of a [`search`].
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Results {
+    /// The files with at least one matching line, ordered by path.
+    pub files: Vec<File>,
+    /// Set if the search stopped after hitting the [`Options::limit`].
+    pub truncated: bool,
+}
+
+/// A file with matching lines.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct File {
+    /// Absolute path to the file from the root of the repo.
+    pub path: String,
+    /// The matching lines in order of appearance.
+    pub lines: Vec<Line>,
+}
+
+/// A single matching line and its surroundings.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Line {
+    /// The line number in the file, starting from 1.
+    pub number: usize,
+    /// The content of the line, without its line ending.
+    pub content: String,
+    /// Byte offsets of the matches within the content.
+    pub matches: Vec<Span>,
+    /// The lines preceding the matching line.
+    pub before: Vec<String>,
+    /// The lines following the matching line.
+    pub after: Vec<String>,
+}
+
+/// Byte offsets of a match within a line.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Span {
+    /// Offset of the first byte of the match.
+    pub start: usize,
+    /// Offset of the byte after the match.
+    pub end: usize,
+}
+
+/// Searches every blob in the tree of the `revision` for the `query`. Binary blobs, i.e. the ones
+/// which aren't valid UTF-8, are skipped.
+///
+/// # Errors
+///
+/// Will return [`Error`] if the query is not a valid regular expression or a surf or git
+/// interaction fails.
+pub fn search<P>(
+    browser: &mut Browser<'_>,
+    repo: &git2::Repository,
+    maybe_revision: Option<Revision<P>>,
+    query: &Query,
+    options: Options,
+) -> Result<Results, Error>
+where
+    P: ToString,
+{
+    let regex = query.compile()?;
+
+    let maybe_revision = maybe_revision.map(Rev::try_from).transpose()?;
+    if let Some(revision) = maybe_revision {
+        browser.rev(revision)?;
+    }
+
+    let head = browser.get().first().id;
+    let blobs = blobs(repo, head).map_err(git::error::Error::from)?;
+
+    let mut results = Results {
+        files: vec![],
+        truncated: false,
+    };
+    let mut remaining = options.limit;
+
+    for (path, oid) in blobs {
+        let blob = repo.find_blob(oid).map_err(git::error::Error::from)?;
+        let content = match std::str::from_utf8(blob.content()) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+
+        let lines = content.lines().collect::<Vec<_>>();
+        let mut matching = vec![];
+
+        for (idx, line) in lines.iter().enumerate() {
+            let matches = regex
+                .find_iter(line)
+                .map(|m| Span {
+                    start: m.start(),
+                    end: m.end(),
+                })
+                .collect::<Vec<_>>();
+            if matches.is_empty() {
+                continue;
+            }
+
+            if remaining == 0 {
+                results.truncated = true;
+                break;
+            }
+            remaining -= 1;
+
+            let context = |range: std::ops::Range<usize>| -> Vec<String> {
+                lines
+                    .get(range)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|line| (*line).to_string())
+                    .collect()
+            };
+            matching.push(Line {
+                number: idx + 1,
+                content: (*line).to_string(),
+                matches,
+                before: context(idx.saturating_sub(options.context)..idx),
+                after: context(idx + 1..(idx + 1 + options.context).min(lines.len())),
+            });
+        }
+
+        if !matching.is_empty() {
+            results.files.push(File {
+                path,
+                lines: matching,
+            });
+        }
+
+        if results.truncated {
+            break;
+        }
+    }
+
+    Ok(results)
+}
+
+/// A file of a revision as handed to a search index.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Document {
+    /// Absolute path to the file from the root of the repo.
+    pub path: String,
+    /// The content of the file, `None` for binary blobs and the ones exceeding
+    /// [`MAX_DOCUMENT_SIZE`].
+    pub content: Option<String>,
+}
+
+/// Lists every blob in the tree of the `revision` as a [`Document`], ordered by path.
+///
+/// # Errors
+///
+/// Will return [`Error`] if a surf or git interaction fails.
+pub fn documents<P>(
+    browser: &mut Browser<'_>,
+    repo: &git2::Repository,
+    maybe_revision: Option<Revision<P>>,
+) -> Result<Vec<Document>, Error>
+where
+    P: ToString,
+{
+    let maybe_revision = maybe_revision.map(Rev::try_from).transpose()?;
+    if let Some(revision) = maybe_revision {
+        browser.rev(revision)?;
+    }
+
+    let head = browser.get().first().id;
+    let blobs = blobs(repo, head).map_err(git::error::Error::from)?;
+
+    let mut documents = Vec::with_capacity(blobs.len());
+    for (path, oid) in blobs {
+        let blob = repo.find_blob(oid).map_err(git::error::Error::from)?;
+        let content = if blob.size() > MAX_DOCUMENT_SIZE {
+            None
+        } else {
+            std::str::from_utf8(blob.content())
+                .ok()
+                .map(ToString::to_string)
+        };
+
+        documents.push(Document { path, content });
+    }
+
+    Ok(documents)
+}
+
+/// Lists the paths and oids of all blobs in the tree of the commit `head`, ordered by path.
+pub(super) fn blobs(
+    repo: &git2::Repository,
+    head: git2::Oid,
+) -> Result<Vec<(String, git2::Oid)>, git2::Error> {
+    let tree = repo.find_commit(head)?.tree()?;
+    let mut blobs = vec![];
+
+    tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+        if entry.kind() == Some(git2::ObjectType::Blob) {
+            if let Some(name) = entry.name() {
+                blobs.push((format!("{}{}", root, name), entry.id()));
+            }
+        }
+        git2::TreeWalkResult::Ok
+    })?;
+    blobs.sort();
+
+    Ok(blobs)
+}
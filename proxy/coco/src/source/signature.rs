@@ -0,0 +1,476 @@
+//! Verification of commit signatures against the key of the peer whose branch is browsed and a
+//! local OpenPGP keyring.
+
+use std::{convert::TryFrom as _, fs, path, process::Command, sync::RwLock};
+
+use serde::Serialize;
+use sha2::Digest as _;
+
+use librad::{keys, peer::PeerId};
+use radicle_keystore::sign;
+use radicle_surf::vcs::git::git2;
+
+use super::Error;
+
+/// First line of an armored SSH signature, as produced by `ssh-keygen -Y sign`.
+const SSH_ARMOR: &str = "-----BEGIN SSH SIGNATURE-----";
+
+/// First line of an armored OpenPGP signature.
+const PGP_ARMOR: &str = "-----BEGIN PGP SIGNATURE-----";
+
+/// Magic preamble of SSH signatures.
+const SSH_MAGIC: &[u8] = b"SSHSIG";
+
+/// Namespace git uses for SSH signatures over commits.
+const SSH_NAMESPACE: &[u8] = b"git";
+
+/// Key type of ed25519 keys in the SSH wire format.
+const SSH_ED25519: &[u8] = b"ssh-ed25519";
+
+/// The program OpenPGP keys are imported with.
+const GPG: &str = "gpg";
+
+/// Prefix of the machine readable status lines of gpg.
+const GPG_STATUS: &str = "[GNUPG:] ";
+
+lazy_static::lazy_static! {
+    // The GnuPG home the keyring was imported into, `None` until a keyring is loaded.
+    static ref GNUPG_HOME: RwLock<Option<path::PathBuf>> = RwLock::new(None);
+}
+
+/// The outcome of verifying the signature of a commit.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum Verification {
+    /// The commit carries no signature.
+    Unsigned,
+    /// The signature was made by a known key.
+    #[serde(rename_all = "camelCase")]
+    Verified {
+        /// The owner of the key the signature was made with.
+        signer: Signer,
+    },
+    /// The commit carries a signature, but it doesn't verify against any known key.
+    Unverified,
+}
+
+/// The owner of a key which made a valid signature.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum Signer {
+    /// The peer whose branch is browsed, identified by their device key.
+    #[serde(rename_all = "camelCase")]
+    Peer {
+        /// The peer the key belongs to.
+        peer_id: PeerId,
+    },
+    /// A key from the local OpenPGP keyring.
+    #[serde(rename_all = "camelCase")]
+    Pgp {
+        /// Fingerprint of the primary key, in upper case hex.
+        fingerprint: String,
+        /// User ids attached to the key, e.g. `Jane Doe <jane@example.com>`.
+        user_ids: Vec<String>,
+    },
+}
+
+/// Replaces the OpenPGP keyring with the armored public keys found in the file at `path`, by
+/// importing them with gpg into the GnuPG `home`. Anything previously stored in `home` is removed.
+///
+/// # Errors
+///
+/// Will return [`Error`] if `home` can't be set up or gpg fails to import the keys.
+pub fn load_keyring(
+    path: impl AsRef<path::Path>,
+    home: impl AsRef<path::Path>,
+) -> Result<(), Error> {
+    let home = home.as_ref();
+    if home.exists() {
+        fs::remove_dir_all(home)?;
+    }
+    fs::create_dir_all(home)?;
+
+    let output = Command::new(GPG)
+        .arg("--homedir")
+        .arg(home)
+        .args(&["--batch", "--no-permission-warning", "--import"])
+        .arg(path.as_ref())
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::Keyring(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    *GNUPG_HOME.write().expect("keyring lock poisoned") = Some(home.to_path_buf());
+
+    Ok(())
+}
+
+/// Checks the signature of the commit `oid`. SSH signatures made with the ed25519 key of `peer_id`
+/// and OpenPGP signatures made with an unexpired and unrevoked key of the local keyring are
+/// considered valid.
+///
+/// # Errors
+///
+/// Will return [`git2::Error`] if the commit can't be read.
+pub fn verify(
+    repo: &git2::Repository,
+    oid: git2::Oid,
+    peer_id: &PeerId,
+) -> Result<Verification, git2::Error> {
+    let (signature, data) = match repo.extract_signature(&oid, None) {
+        Ok(extracted) => extracted,
+        Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(Verification::Unsigned),
+        Err(err) => return Err(err),
+    };
+    let signature = String::from_utf8_lossy(&signature);
+    let signature = signature.trim();
+
+    let signer = if signature.starts_with(SSH_ARMOR) {
+        verify_ssh(signature, &data, peer_id)
+    } else if signature.starts_with(PGP_ARMOR) {
+        verify_pgp(repo, oid)
+    } else {
+        None
+    };
+
+    Ok(
+        signer.map_or(Verification::Unverified, |signer| Verification::Verified {
+            signer,
+        }),
+    )
+}
+
+/// Verifies an armored SSH signature over `data`, which is valid if made with the device key of
+/// `peer_id`.
+fn verify_ssh(armored: &str, data: &[u8], peer_id: &PeerId) -> Option<Signer> {
+    let encoded = armored
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .collect::<String>();
+    let blob = base64::decode(encoded).ok()?;
+
+    let mut reader = blob.strip_prefix(SSH_MAGIC)?;
+    let _version = read_u32(&mut reader)?;
+    let public_key = read_string(&mut reader)?;
+    let namespace = read_string(&mut reader)?;
+    let reserved = read_string(&mut reader)?;
+    let hash_algorithm = read_string(&mut reader)?;
+    let signature = read_string(&mut reader)?;
+
+    if namespace != SSH_NAMESPACE {
+        return None;
+    }
+
+    let mut key_reader = public_key;
+    if read_string(&mut key_reader)? != SSH_ED25519 {
+        return None;
+    }
+    let key = keys::PublicKey::from_slice(read_string(&mut key_reader)?)?;
+    if PeerId::from(key.clone()) != *peer_id {
+        return None;
+    }
+
+    let mut signature_reader = signature;
+    if read_string(&mut signature_reader)? != SSH_ED25519 {
+        return None;
+    }
+    let signature = <[u8; 64]>::try_from(read_string(&mut signature_reader)?).ok()?;
+
+    let digest = match hash_algorithm {
+        b"sha256" => sha2::Sha256::digest(data).to_vec(),
+        b"sha512" => sha2::Sha512::digest(data).to_vec(),
+        _ => return None,
+    };
+    let mut message = SSH_MAGIC.to_vec();
+    for field in &[namespace, reserved, hash_algorithm, digest.as_slice()] {
+        write_string(&mut message, field);
+    }
+
+    if !keys::Signature::from(sign::ed25519::Signature(signature)).verify(&message, &key) {
+        return None;
+    }
+
+    Some(Signer::Peer {
+        peer_id: peer_id.clone(),
+    })
+}
+
+/// Verifies the OpenPGP signature of the commit `oid` with gpg, against the keys imported by
+/// [`load_keyring`]. Signatures made with expired or revoked keys, and expired signatures, are not
+/// valid.
+fn verify_pgp(repo: &git2::Repository, oid: git2::Oid) -> Option<Signer> {
+    let home = GNUPG_HOME.read().expect("keyring lock poisoned").clone()?;
+    let output = Command::new("git")
+        .arg("--git-dir")
+        .arg(repo.path())
+        .args(&["verify-commit", "--raw"])
+        .arg(oid.to_string())
+        .env("GNUPGHOME", &home)
+        .output()
+        .ok()?;
+    let fingerprint = good_signature(&String::from_utf8_lossy(&output.stderr))?;
+
+    Some(Signer::Pgp {
+        user_ids: user_ids(&home, &fingerprint),
+        fingerprint,
+    })
+}
+
+/// Finds the fingerprint of the primary key which made a good signature in the machine readable
+/// `status` gpg reported for a verification.
+fn good_signature(status: &str) -> Option<String> {
+    let mut good = false;
+    let mut fingerprint = None;
+
+    for line in status
+        .lines()
+        .filter_map(|line| line.strip_prefix(GPG_STATUS))
+    {
+        let mut fields = line.split(' ');
+        match fields.next() {
+            Some("GOODSIG") => good = true,
+            // gpg reports these instead of `GOODSIG` for expired or revoked keys and for expired
+            // signatures.
+            Some("BADSIG") | Some("ERRSIG") | Some("EXPSIG") | Some("EXPKEYSIG")
+            | Some("REVKEYSIG") => return None,
+            // The fingerprint of the primary key comes last.
+            Some("VALIDSIG") => fingerprint = fields.last().map(ToString::to_string),
+            _ => {},
+        }
+    }
+
+    if good {
+        fingerprint
+    } else {
+        None
+    }
+}
+
+/// Lists the user ids of the key with the given `fingerprint` found in the GnuPG `home`.
+fn user_ids(home: &path::Path, fingerprint: &str) -> Vec<String> {
+    let output = match Command::new(GPG)
+        .arg("--homedir")
+        .arg(home)
+        .args(&[
+            "--batch",
+            "--no-permission-warning",
+            "--with-colons",
+            "--list-keys",
+        ])
+        .arg(fingerprint)
+        .output()
+    {
+        Ok(output) => output,
+        Err(_err) => return vec![],
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| line.starts_with("uid:"))
+        .filter_map(|line| line.split(':').nth(9))
+        // Colons and backslashes are escaped in the listing.
+        .map(|user_id| user_id.replace("\\x3a", ":").replace("\\x5c", "\\"))
+        .collect()
+}
+
+/// Reads a big-endian `u32` off the front of `reader`.
+fn read_u32(reader: &mut &[u8]) -> Option<u32> {
+    let bytes = reader.get(..4)?;
+    *reader = reader.get(4..)?;
+    Some(u32::from_be_bytes(<[u8; 4]>::try_from(bytes).ok()?))
+}
+
+/// Reads a length-prefixed string off the front of `reader`, as encoded in the SSH wire format.
+fn read_string<'a>(reader: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let len = usize::try_from(read_u32(reader)?).ok()?;
+    let bytes = reader.get(..len)?;
+    *reader = reader.get(len..)?;
+    Some(bytes)
+}
+
+/// Appends `bytes` to `buffer` as a length-prefixed string in the SSH wire format.
+fn write_string(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    #[allow(clippy::cast_possible_truncation)]
+    buffer.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    buffer.extend_from_slice(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use librad::{keys::SecretKey, peer::PeerId};
+    use radicle_keystore::sign;
+    use radicle_surf::vcs::git::git2;
+    use sha2::Digest as _;
+
+    use super::{
+        good_signature, read_string, read_u32, verify, write_string, Signer, Verification,
+        SSH_ARMOR, SSH_ED25519, SSH_MAGIC, SSH_NAMESPACE,
+    };
+
+    /// Builds the content of a commit with an empty tree and the given `message`.
+    fn commit_content(repo: &git2::Repository, message: &str) -> Result<String, git2::Error> {
+        let tree = repo.find_tree(repo.treebuilder(None)?.write()?)?;
+        let signature = git2::Signature::now("Jane Doe", "jane@example.com")?;
+        let content = repo.commit_create_buffer(&signature, &signature, message, &tree, &[])?;
+
+        Ok(String::from_utf8_lossy(&content).into_owned())
+    }
+
+    /// Signs `data` with `key` the way `ssh-keygen -Y sign -n git` does, claiming the signature
+    /// was made with `claimed`.
+    fn ssh_signature(key: &SecretKey, claimed: &SecretKey, data: &[u8]) -> String {
+        let digest = sha2::Sha512::digest(data);
+        let mut message = SSH_MAGIC.to_vec();
+        for field in &[SSH_NAMESPACE, &b""[..], &b"sha512"[..], digest.as_slice()] {
+            write_string(&mut message, field);
+        }
+        let signature =
+            futures::executor::block_on(sign::Signer::sign(key, &message)).expect("signing failed");
+
+        let mut public_key = vec![];
+        write_string(&mut public_key, SSH_ED25519);
+        write_string(&mut public_key, &sign::Signer::public_key(claimed).0);
+        let mut signature_blob = vec![];
+        write_string(&mut signature_blob, SSH_ED25519);
+        write_string(&mut signature_blob, &signature.0);
+
+        let mut blob = SSH_MAGIC.to_vec();
+        blob.extend_from_slice(&1_u32.to_be_bytes());
+        for field in &[
+            public_key.as_slice(),
+            SSH_NAMESPACE,
+            &b""[..],
+            &b"sha512"[..],
+            signature_blob.as_slice(),
+        ] {
+            write_string(&mut blob, field);
+        }
+
+        let encoded = base64::encode(blob);
+        let lines = encoded
+            .as_bytes()
+            .chunks(70)
+            .map(String::from_utf8_lossy)
+            .collect::<Vec<_>>();
+        format!(
+            "{}\n{}\n-----END SSH SIGNATURE-----",
+            SSH_ARMOR,
+            lines.join("\n")
+        )
+    }
+
+    #[test]
+    fn ssh_signatures() -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_dir = tempfile::tempdir()?;
+        let repo = git2::Repository::init(tmp_dir.path())?;
+        let key = SecretKey::new();
+        let other = SecretKey::new();
+        let peer_id = PeerId::from(key.public());
+        let content = commit_content(&repo, "signed")?;
+
+        let oid = repo
+            .odb()?
+            .write(git2::ObjectType::Commit, content.as_bytes())?;
+        assert_eq!(verify(&repo, oid, &peer_id)?, Verification::Unsigned);
+
+        // Signed with the key of the peer.
+        let signature = ssh_signature(&key, &key, content.as_bytes());
+        let oid = repo.commit_signed(&content, &signature, None)?;
+        assert_eq!(
+            verify(&repo, oid, &peer_id)?,
+            Verification::Verified {
+                signer: Signer::Peer {
+                    peer_id: peer_id.clone()
+                }
+            }
+        );
+
+        // Signed with the key of another peer.
+        let signature = ssh_signature(&other, &other, content.as_bytes());
+        let oid = repo.commit_signed(&content, &signature, None)?;
+        assert_eq!(verify(&repo, oid, &peer_id)?, Verification::Unverified);
+
+        // Signed with another key, claiming to be the key of the peer.
+        let signature = ssh_signature(&other, &key, content.as_bytes());
+        let oid = repo.commit_signed(&content, &signature, None)?;
+        assert_eq!(verify(&repo, oid, &peer_id)?, Verification::Unverified);
+
+        // Signed by the peer, but the commit was changed afterwards.
+        let signature = ssh_signature(&key, &key, content.as_bytes());
+        let oid = repo.commit_signed(&commit_content(&repo, "tampered")?, &signature, None)?;
+        assert_eq!(verify(&repo, oid, &peer_id)?, Verification::Unverified);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pgp_status() {
+        let fingerprint = "D8E5E9A0E1D4A87B0C1B6EFE3C2A8A1B2D3E4F50";
+        let valid = format!(
+            "VALIDSIG 1B2D3E4F50 2020-12-01 1606780800 0 4 0 22 10 00 {}",
+            fingerprint
+        );
+        let status = |lines: &[&str]| {
+            lines
+                .iter()
+                .map(|line| format!("[GNUPG:] {}\n", line))
+                .collect::<String>()
+        };
+
+        assert_eq!(
+            good_signature(&status(&[
+                "NEWSIG",
+                "GOODSIG 3C2A8A1B2D3E4F50 Jane Doe <jane@example.com>",
+                &valid,
+                "TRUST_UNDEFINED 0 pgp",
+            ])),
+            Some(fingerprint.to_string())
+        );
+        assert_eq!(
+            good_signature(&status(&[
+                "NEWSIG",
+                "EXPKEYSIG 3C2A8A1B2D3E4F50 Jane Doe <jane@example.com>",
+                &valid,
+            ])),
+            None
+        );
+        assert_eq!(
+            good_signature(&status(&[
+                "NEWSIG",
+                "KEYREVOKED",
+                "REVKEYSIG 3C2A8A1B2D3E4F50 Jane Doe <jane@example.com>",
+                &valid,
+            ])),
+            None
+        );
+        assert_eq!(
+            good_signature(&status(&[
+                "NEWSIG",
+                "ERRSIG 3C2A8A1B2D3E4F50 22 10 00 1606780800 9"
+            ])),
+            None
+        );
+    }
+
+    #[test]
+    fn wire_strings_roundtrip() {
+        let mut buffer = vec![];
+        write_string(&mut buffer, b"ssh-ed25519");
+        write_string(&mut buffer, b"");
+
+        let mut reader = buffer.as_slice();
+        assert_eq!(read_string(&mut reader), Some(&b"ssh-ed25519"[..]));
+        assert_eq!(read_string(&mut reader), Some(&b""[..]));
+        assert_eq!(read_u32(&mut reader), None);
+    }
+
+    #[test]
+    fn truncated_strings_are_rejected() {
+        let mut reader = &[0, 0, 0, 8, b'a'][..];
+        assert_eq!(read_string(&mut reader), None);
+    }
+}
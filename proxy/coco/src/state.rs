@@ -387,6 +387,49 @@ impl State {
     where
         F: FnOnce(&mut git::Browser) -> Result<T, source::Error> + Send,
     {
+        let (namespace, branch) = Self::browser_reference(&reference)?;
+        let monorepo = self.monorepo();
+        let repo = git::Repository::new(monorepo).map_err(source::Error::from)?;
+        let mut browser = git::Browser::new_with_namespace(&repo, &namespace, branch)
+            .map_err(source::Error::from)?;
+
+        callback(&mut browser).map_err(Error::from)
+    }
+
+    /// Provide a repo [`git::Browser`] like [`State::with_browser`], alongside a handle on the
+    /// underlying [`git2::Repository`] of the monorepo. The latter is needed for the operations
+    /// `radicle_surf` doesn't cover, e.g. blame. References are looked up in the same namespace
+    /// as the browser's.
+    ///
+    /// # Errors
+    ///   * If the namespace of the reference could not be converted to a [`git::Namespace`].
+    ///   * If we could not open the backing storage.
+    ///   * If we could not initialise the `Browser`.
+    ///   * If the callback provided returned an error.
+    pub async fn with_browser_and_repo<F, T>(
+        &self,
+        reference: NamespacedRef<namespace::Legacy, Single>,
+        callback: F,
+    ) -> Result<T, Error>
+    where
+        F: FnOnce(&mut git::Browser, &git2::Repository) -> Result<T, source::Error> + Send,
+    {
+        let (namespace, branch) = Self::browser_reference(&reference)?;
+        let monorepo = self.monorepo();
+        let git2_repo = git2::Repository::open(&monorepo)?;
+        git2_repo.set_namespace(&reference.namespace().to_string())?;
+        let repo = git::Repository::new(monorepo).map_err(source::Error::from)?;
+        let mut browser = git::Browser::new_with_namespace(&repo, &namespace, branch)
+            .map_err(source::Error::from)?;
+
+        callback(&mut browser, &git2_repo).map_err(Error::from)
+    }
+
+    /// Translate a [`NamespacedRef`] into the [`git::Namespace`] and [`git::Branch`] a
+    /// [`git::Browser`] is initialised with.
+    fn browser_reference(
+        reference: &NamespacedRef<namespace::Legacy, Single>,
+    ) -> Result<(git::Namespace, git::Branch), Error> {
         let namespace = git::Namespace::try_from(reference.namespace().to_string().as_str())
             .map_err(source::Error::from)?;
         let branch = match reference.remote {
@@ -396,12 +439,8 @@ impl State {
                 &peer.to_string(),
             ),
         };
-        let monorepo = self.monorepo();
-        let repo = git::Repository::new(monorepo).map_err(source::Error::from)?;
-        let mut browser = git::Browser::new_with_namespace(&repo, &namespace, branch)
-            .map_err(source::Error::from)?;
 
-        callback(&mut browser).map_err(Error::from)
+        Ok((namespace, branch))
     }
 
     /// This method helps us get a branch for a given [`RadUrn`] and optional [`PeerId`].
@@ -489,6 +528,7 @@ impl State {
     /// # Errors
     ///
     /// Will error if:
+    ///     * The files of the [`project::Template`] can't be read.
     ///     * The signing of the project metadata fails.
     ///     * The interaction with `librad` [`librad::git::storage::Storage`] fails.
     pub async fn init_project(
@@ -502,11 +542,34 @@ impl State {
         let local_peer_id = self.api.peer_id();
         let url = LocalUrl::from_urn(meta.urn(), local_peer_id);
 
-        let repository = project
-            .validate(url)
+        project
+            .guard_template()
             .map_err(project::create::Error::from)?;
+        let files = match project.template.clone() {
+            None => None,
+            Some(project::Template::Directory { path }) => Some(
+                project::create::template::Files::from_directory(&path)
+                    .map_err(project::create::Error::from)?,
+            ),
+            Some(project::Template::Project { urn, revision }) => {
+                let default_branch = self.find_default_branch(urn).await?;
+                Some(
+                    self.with_browser_and_repo(default_branch, |browser, repo| {
+                        project::create::template::Files::from_revision(browser, repo, revision)
+                    })
+                    .await?,
+                )
+            },
+        };
 
-        let meta = {
+        // Imports clone the repository while validating.
+        let repository = tokio::task::spawn_blocking(move || project.validate(url, files))
+            .await
+            .expect("blocking validation failed")
+            .map_err(project::create::Error::from)?;
+        let imported_path = repository.imported_path();
+
+        let created = async {
             let results = self.transport_results();
             let (meta, repo) = self
                 .api
@@ -524,14 +587,169 @@ impl State {
             Self::process_transport_results(&results)?;
             let include_path = self.update_include(meta.urn()).await?;
             include::set_include_path(&repo, include_path)?;
+            Ok::<_, Error>(meta)
+        }
+        .await;
+        if let (Err(_), Some(path)) = (&created, imported_path) {
+            log::debug!("Removing import of failed project @ '{}'", path.display());
+            if let Err(err) = std::fs::remove_dir_all(&path) {
+                log::warn!("Failed to remove '{}': {}", path.display(), err);
+            }
+        }
+        let meta = created?;
+
+        crate::peer::gossip::announce(self, &meta.urn(), None).await;
+
+        Ok(meta)
+    }
+
+    /// Fork the [`librad_project::Project`] under `urn` into a new project called `name`, owned
+    /// by the `owner`. The branches and tags of the fork are seeded from the view of `peer_id`,
+    /// or the local peer if it is `None`, and the upstream is recorded in its metadata.
+    /// The fork is not announced, see [`crate::peer::announce_project`].
+    ///
+    /// # Errors
+    ///
+    /// Will error if:
+    ///     * The upstream project is not present in the monorepo.
+    ///     * The signing of the project metadata fails.
+    ///     * The interaction with `librad` [`librad::git::storage::Storage`] fails.
+    ///     * Pushing the references of the upstream into the fork fails.
+    pub async fn fork_project(
+        &self,
+        owner: &User,
+        urn: RadUrn,
+        peer_id: Option<PeerId>,
+        name: String,
+    ) -> Result<librad_project::Project<entity::Draft>, Error> {
+        let peer_id = peer_id.filter(|peer_id| *peer_id != self.peer_id());
+        let upstream = self.get_project(urn.clone(), peer_id).await?;
+        let mut meta = project::fork::build(
+            &upstream,
+            &urn,
+            name,
+            owner,
+            self.signer.public_key().into(),
+        )?;
+        meta.sign_by_user(&self.signer, owner)?;
+
+        let url = LocalUrl::from_urn(meta.urn(), self.api.peer_id());
+        let monorepo = self.monorepo();
+        let refspecs = project::fork::refspecs(&urn, peer_id);
+
+        let meta = {
+            let results = self.transport_results();
+            let meta = self
+                .api
+                .with_storage(move |storage| {
+                    let _ = storage.create_repo(&meta)?;
+                    log::debug!("Created fork '{}#{}' of '{}'", meta.urn(), meta.name(), urn);
+
+                    let repo = git2::Repository::open(monorepo)?;
+                    let mut remote = repo.remote_anonymous(&url.to_string())?;
+                    remote.push(&refspecs, None)?;
+
+                    Ok::<_, Error>(meta)
+                })
+                .await??;
+            Self::process_transport_results(&results)?;
+            self.update_include(meta.urn()).await?;
             meta
         };
 
-        crate::peer::gossip::announce(self, &meta.urn(), None).await;
+        Ok(meta)
+    }
+
+    /// Produce a new revision of the [`librad_project::Project`] under `urn` with the changes of
+    /// `update` applied, signed by the `owner`.
+    /// The new revision is not announced, see [`crate::peer::announce_project`].
+    ///
+    /// # Errors
+    ///
+    /// Will error if:
+    ///     * The `owner` is not a maintainer of the project.
+    ///     * The new default branch doesn't exist.
+    ///     * The signing of the project metadata fails.
+    ///     * The interaction with `librad` [`librad::git::storage::Storage`] fails.
+    pub async fn update_project(
+        &self,
+        owner: &User,
+        urn: RadUrn,
+        update: project::Update,
+    ) -> Result<librad_project::Project<entity::Draft>, Error> {
+        if let Some(default_branch) = &update.default_branch {
+            self.get_branch(urn.clone(), None, default_branch.as_str().to_string())
+                .await?;
+        }
+
+        let project = self.get_project(urn, None).await?;
+        let mut meta = update.build(&project, owner)?;
+        meta.sign_by_user(&self.signer, owner)?;
+
+        let meta = self
+            .api
+            .with_storage(move |storage| {
+                storage.update_metadata(&meta)?;
+                log::debug!("Updated project '{}#{}'", meta.urn(), meta.name());
+
+                Ok::<_, Error>(meta)
+            })
+            .await??;
 
         Ok(meta)
     }
 
+    /// Remove the [`librad_project::Project`] under `urn` from the monorepo. All tracked peers
+    /// are untracked, and the references of the project namespace and its include file are
+    /// removed.
+    ///
+    /// # Errors
+    ///
+    /// Will error if:
+    ///     * The project is not present in the monorepo.
+    ///     * The interaction with `librad` [`librad::git::storage::Storage`] fails.
+    ///     * The references or the include file can't be removed.
+    pub async fn delete_project(&self, urn: RadUrn) -> Result<(), Error> {
+        {
+            let urn = urn.clone();
+            self.api
+                .with_storage(move |storage| {
+                    let tracked = storage
+                        .open_repo(urn.clone())?
+                        .tracked()?
+                        .collect::<Vec<_>>();
+                    for peer_id in tracked {
+                        storage.untrack(&urn, &peer_id)?;
+                    }
+
+                    Ok::<_, Error>(())
+                })
+                .await??;
+        }
+
+        let monorepo = git2::Repository::open(self.monorepo())?;
+        let names = monorepo
+            .references_glob(&format!("refs/namespaces/{}/*", urn.id))?
+            .names()
+            .map(|name| name.map(ToString::to_string))
+            .collect::<Result<Vec<_>, _>>()?;
+        log::debug!("Deleting {} references of project '{}'", names.len(), urn);
+        for name in names {
+            monorepo.find_reference(&name)?.delete()?;
+        }
+
+        let include_path = self
+            .paths()
+            .git_includes_dir()
+            .join(format!("{}.inc", urn.id));
+        match std::fs::remove_file(&include_path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+            _ => {},
+        }
+
+        Ok(())
+    }
+
     /// Create a [`user::User`] with the provided `handle`. This assumes that you are creating a
     /// user that uses the secret key the `PeerApi` was configured with.
     ///
@@ -802,6 +1020,7 @@ mod test {
             },
             description: "rad git tricks".to_string(),
             default_branch: OneLevel::from(reflike!("dope")),
+            template: None,
         }
     }
 
@@ -813,6 +1032,7 @@ mod test {
             },
             description: "the people".to_string(),
             default_branch: OneLevel::from(reflike!("power")),
+            template: None,
         }
     }
 
@@ -40,6 +40,10 @@ pub enum Error {
     #[error(transparent)]
     Include(#[from] librad::git::include::Error),
 
+    /// An I/O error occurred while cleaning up after a project.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
     /// Entity meta error.
     #[error(transparent)]
     Meta(#[from] entity::Error),
@@ -72,6 +76,10 @@ pub enum Error {
     #[error(transparent)]
     UriParse(#[from] uri::path::ParseError),
 
+    /// An error occurred while updating the metadata of a project.
+    #[error(transparent)]
+    Update(#[from] crate::project::update::Error),
+
     /// Verifcation error.
     #[error(transparent)]
     Verification(#[from] entity::HistoryVerificationError),
@@ -141,6 +141,7 @@ pub fn radicle_project(path: PathBuf) -> project::Create {
         },
         description: "the people".to_string(),
         default_branch: OneLevel::from(reflike!("power")),
+        template: None,
     }
 }
 
@@ -153,5 +154,6 @@ pub fn shia_le_pathbuf(path: PathBuf) -> project::Create {
         },
         description: "do".to_string(),
         default_branch: OneLevel::from(reflike!("it")),
+        template: None,
     }
 }
@@ -90,6 +90,19 @@ export const post = async <I, D>(
     })
   );
 
+export const patch = async <I, D>(
+  endpoint: string,
+  body: I,
+  options?: Options
+): Promise<D> =>
+  http<D>(
+    request(endpoint, {
+      method: "PATCH",
+      body: JSON.stringify(body),
+      ...options,
+    })
+  );
+
 export const put = async <I, D>(
   endpoint: string,
   body: I,
@@ -18,11 +18,13 @@ export interface Metadata {
   defaultBranch: string;
   description?: string;
   maintainers: string[];
+  upstream?: Urn;
 }
 
 export enum RepoType {
   New = "new",
   Existing = "existing",
+  Import = "import",
 }
 
 export interface New {
@@ -36,7 +38,33 @@ export interface Existing {
   path: string;
 }
 
-type Repo = New | Existing;
+export interface Import {
+  type: RepoType.Import;
+  url: string;
+  path: string;
+}
+
+type Repo = New | Existing | Import;
+
+export enum TemplateType {
+  Directory = "directory",
+  Project = "project",
+}
+
+export interface DirectoryTemplate {
+  type: TemplateType.Directory;
+  path: string;
+}
+
+export interface ProjectTemplate {
+  type: TemplateType.Project;
+  urn: Urn;
+  revision?: source.Revision;
+}
+
+// Files of the template may use the placeholders `{{name}}`, `{{description}}` and
+// `{{defaultBranch}}`.
+export type Template = DirectoryTemplate | ProjectTemplate;
 
 export enum Role {
   Contributor = "contributor",
@@ -87,10 +115,18 @@ export interface User {
   role: Role;
 }
 
+export interface Language {
+  name: string;
+  bytes: number;
+  percentage: number;
+}
+
 export interface Stats {
   branches: number;
   commits: number;
   contributors: number;
+  // Only present when fetching a single project.
+  languages?: Language[];
 }
 
 export interface Project {
@@ -158,6 +194,7 @@ interface CreateInput {
   repo: Repo;
   description?: string;
   defaultBranch: string;
+  template?: Template;
 }
 
 const update = (msg: Msg): void => {
@@ -201,6 +238,20 @@ export const create = (input: CreateInput): Promise<Project> => {
   return api.post<CreateInput, Project>(`projects`, input);
 };
 
+export interface UpdateInput {
+  description?: string;
+  defaultBranch?: string;
+  addMaintainers?: Urn[];
+  removeMaintainers?: Urn[];
+}
+
+export const updateMetadata = (
+  urn: Urn,
+  input: UpdateInput
+): Promise<Project> => {
+  return api.patch<UpdateInput, Project>(`projects/${urn}`, input);
+};
+
 interface CheckoutInput {
   peerId?: string;
   path: string;
@@ -228,6 +279,34 @@ export const fetchLocalState = event.create<Kind, Msg>(
   update
 );
 
+interface ForkInput {
+  name: string;
+  peerId?: identity.PeerId;
+}
+
+export const fork = (
+  projectUrn: Urn,
+  name: string,
+  peerId?: identity.PeerId
+): Promise<Project> => {
+  return api.post<ForkInput, Project>(`projects/${projectUrn}/fork`, {
+    name,
+    peerId,
+  });
+};
+
+export const archive = (projectUrn: Urn): Promise<boolean> => {
+  return api.put<null, boolean>(`projects/${projectUrn}/archive`, null);
+};
+
+export const unarchive = (projectUrn: Urn): Promise<boolean> => {
+  return api.put<null, boolean>(`projects/${projectUrn}/unarchive`, null);
+};
+
+export const remove = (projectUrn: Urn): Promise<null> => {
+  return api.del(`projects/${projectUrn}`);
+};
+
 export const cancelRequest = (urn: string): Promise<null> => {
   return api.del(`projects/requests/${urn}`);
 };
@@ -236,6 +315,10 @@ export const fetch = (projectUrn: Urn): Promise<Project> => {
   return api.get<Project>(`projects/${projectUrn}`);
 };
 
+export const fetchArchived = (): Promise<Project[]> => {
+  return api.get<Project[]>("projects/archived");
+};
+
 export const fetchFailed = (): Promise<Project[]> => {
   return api.get<Project[]>("projects/failed");
 };
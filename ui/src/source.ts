@@ -16,6 +16,15 @@ export interface Person {
   name: string;
 }
 
+export type Signer =
+  | { type: "peer"; peerId: PeerId }
+  | { type: "pgp"; fingerprint: string; userIds: string[] };
+
+export type Verification =
+  | { status: "unsigned" }
+  | { status: "verified"; signer: Signer }
+  | { status: "unverified" };
+
 export interface CommitHeader {
   author: Person;
   committer: Person;
@@ -23,6 +32,7 @@ export interface CommitHeader {
   description: string;
   sha1: Sha1;
   summary: string;
+  signature?: Verification;
 }
 
 export interface CommitStats {
@@ -30,8 +40,19 @@ export interface CommitStats {
   deletions: number;
 }
 
+export interface BranchRef {
+  name: string;
+  peerId: PeerId | null;
+}
+
+export interface TagRef {
+  name: string;
+  peerId: PeerId | null;
+}
+
 export interface Commit {
-  branch: string;
+  branches: BranchRef[];
+  tags: TagRef[];
   diff: diff.Diff;
   header: CommitHeader;
   stats: CommitStats;
@@ -46,7 +67,7 @@ interface Stats {
 
 interface Commits {
   headers: CommitHeader[];
-  stats: Stats;
+  next: Sha1 | null;
 }
 
 export interface CommitsHistory {
@@ -54,6 +75,24 @@ export interface CommitsHistory {
   stats: Stats;
 }
 
+export interface GraphEdge {
+  sha1: Sha1;
+  lane: number;
+}
+
+export interface GraphNode {
+  header: CommitHeader;
+  lane: number;
+  parents: GraphEdge[];
+  branches: BranchRef[];
+  tags: string[];
+}
+
+export interface Graph {
+  nodes: GraphNode[];
+  lanes: number;
+}
+
 interface CommitGroup {
   time: string;
   commits: CommitHeader[];
@@ -64,12 +103,25 @@ type CommitHistory = CommitGroup[];
 export enum ObjectType {
   Blob = "BLOB",
   Tree = "TREE",
+  Submodule = "SUBMODULE",
+  Symlink = "SYMLINK",
+}
+
+export interface Submodule {
+  commit: string;
+  url: string | null;
+}
+
+export interface Symlink {
+  target: string;
 }
 
 interface Info {
   name: string;
   objectType: ObjectType;
   lastCommit: CommitHeader;
+  submodule?: Submodule;
+  symlink?: Symlink;
 }
 
 export interface LocalState {
@@ -120,6 +172,15 @@ export interface Tag {
   name: string;
 }
 
+export interface TagDetails {
+  name: string;
+  target: CommitHeader;
+  annotated: boolean;
+  tagger: Person | null;
+  time: number;
+  message: string | null;
+}
+
 export interface Sha {
   type: RevisionType.Sha;
   sha: string;
@@ -177,23 +238,33 @@ export const fetchCommit = (projectUrn: Urn, sha1: Sha1): Promise<Commit> => {
   return api.get<Commit>(`source/commit/${projectUrn}/${sha1}`);
 };
 
-export const fetchCommits = (
+export const fetchCommits = async (
   projectUrn: Urn,
   peerId: PeerId,
   revision: Revision
 ): Promise<CommitsHistory> => {
-  return api
-    .get<Commits>(`source/commits/${projectUrn}/`, {
-      query: {
-        revision: { ...revision, peerId },
-      },
-    })
-    .then(response => {
-      return {
-        stats: response.stats,
-        history: groupCommits(response.headers),
-      };
-    });
+  const query = {
+    revision: { ...revision, peerId },
+  };
+  const [commits, stats] = await Promise.all([
+    api.get<Commits>(`source/commits/${projectUrn}/`, { query }),
+    api.get<Stats>(`source/commits/${projectUrn}/stats`, { query }),
+  ]);
+
+  return {
+    stats,
+    history: groupCommits(commits.headers),
+  };
+};
+
+export const fetchGraph = (
+  projectUrn: Urn,
+  revisions: Revision[],
+  limit?: number
+): Promise<Graph> => {
+  return api.get<Graph>(`source/graph/${projectUrn}`, {
+    query: { revisions, limit },
+  });
 };
 
 export const fetchReadme = async (
@@ -254,6 +325,27 @@ export const fetchTags = (projectUrn: Urn, peerId?: PeerId): Promise<Tag[]> => {
     );
 };
 
+export const fetchTagsDetails = (
+  projectUrn: Urn,
+  peerId?: PeerId
+): Promise<TagDetails[]> => {
+  return api.get<TagDetails[]>(`source/tags/${projectUrn}`, {
+    query: {
+      peerId,
+      details: true,
+    },
+  });
+};
+
+export const fetchTag = (
+  projectUrn: Urn,
+  name: string
+): Promise<TagDetails> => {
+  return api.get<TagDetails>(
+    `source/tag/${projectUrn}/${encodeURIComponent(name)}`
+  );
+};
+
 export const fetchTree = (
   projectUrn: Urn,
   peerId: PeerId,
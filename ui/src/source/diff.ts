@@ -91,6 +91,7 @@ export interface Plain {
 export type FileDiff = Binary | Plain;
 
 export interface CopiedFile {
+  diff: FileDiff;
   newPath: string;
   oldPath: string;
 }
@@ -104,6 +105,7 @@ export interface ModifiedFile {
 }
 
 export interface MovedFile {
+  diff: FileDiff;
   newPath: string;
   oldPath: string;
 }